@@ -0,0 +1,242 @@
+// src/bloom.rs
+use crate::types::{RustFinderError, SubdomainResult};
+use std::collections::hash_map::DefaultHasher;
+use std::fs::{self, File};
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+// Capacidade do primeiro filtro; cada filtro novo tem o dobro, mantendo a taxa de falso positivo
+const INITIAL_FILTER_CAPACITY: usize = 1_000_000;
+const FALSE_POSITIVE_RATE: f64 = 0.001;
+// Chaves confirmadas ficam em disco, espalhadas por hash para limitar a leitura de cada confirmação
+const KEY_BUCKETS: u64 = 256;
+
+// Filtro de Bloom para deduplicação com memória limitada (--low-memory).
+// Pode dar falso positivo ("já visto" para um nome novo), nunca falso negativo
+#[derive(Debug, Clone)]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u32,
+}
+
+impl BloomFilter {
+    pub fn new(capacity: usize, false_positive_rate: f64) -> Self {
+        let capacity = capacity.max(1) as f64;
+        let rate = false_positive_rate.clamp(1e-9, 0.5);
+        let ln2 = std::f64::consts::LN_2;
+
+        let num_bits = (-(capacity * rate.ln()) / (ln2 * ln2)).ceil().max(64.0) as u64;
+        let num_hashes = ((num_bits as f64 / capacity) * ln2).round().clamp(1.0, 32.0) as u32;
+
+        Self {
+            bits: vec![0; num_bits.div_ceil(64) as usize],
+            num_bits,
+            num_hashes,
+        }
+    }
+
+    // Double hashing: h1 + i*h2 gera os k índices a partir de dois hashes
+    fn indexes<T: Hash + ?Sized>(&self, item: &T) -> impl Iterator<Item = u64> {
+        let mut first = DefaultHasher::new();
+        item.hash(&mut first);
+        let h1 = first.finish();
+
+        let mut second = DefaultHasher::new();
+        0x9e37_79b9_7f4a_7c15u64.hash(&mut second);
+        item.hash(&mut second);
+        let h2 = second.finish() | 1;
+
+        let num_bits = self.num_bits;
+        (0..self.num_hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % num_bits)
+    }
+
+    pub fn contains<T: Hash + ?Sized>(&self, item: &T) -> bool {
+        self.indexes(item).all(|index| self.bits[(index / 64) as usize] & (1 << (index % 64)) != 0)
+    }
+
+    // Retorna true se o item (provavelmente) ainda não estava no filtro
+    pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) -> bool {
+        let indexes: Vec<u64> = self.indexes(item).collect();
+        let mut inserted = false;
+        for index in indexes {
+            let word = &mut self.bits[(index / 64) as usize];
+            let mask = 1 << (index % 64);
+            if *word & mask == 0 {
+                *word |= mask;
+                inserted = true;
+            }
+        }
+        inserted
+    }
+
+    pub fn memory_bytes(&self) -> usize {
+        self.bits.len() * std::mem::size_of::<u64>()
+    }
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> RustFinderError {
+    RustFinderError::OutputError(format!("Low-memory dedup I/O error on {:?}: {}", path, e))
+}
+
+// Dedup do --low-memory: uma ausência no filtro é nome novo com certeza; uma presença é confirmada
+// nas chaves gravadas em disco. Os resultados únicos também vão para disco até o relatório final
+pub struct SpilledDedup {
+    filters: Vec<BloomFilter>,
+    filter_capacity: usize,
+    filter_len: usize,
+    dir: PathBuf,
+    buckets: Vec<Option<BufWriter<File>>>,
+    results: BufWriter<File>,
+    len: usize,
+}
+
+impl SpilledDedup {
+    pub fn new(dir: PathBuf) -> Result<Self, RustFinderError> {
+        fs::create_dir_all(&dir).map_err(|e| io_error(&dir, e))?;
+        let results_path = dir.join("results.jsonl");
+        let results = File::create(&results_path).map_err(|e| io_error(&results_path, e))?;
+        Ok(Self {
+            filters: vec![BloomFilter::new(INITIAL_FILTER_CAPACITY, FALSE_POSITIVE_RATE)],
+            filter_capacity: INITIAL_FILTER_CAPACITY,
+            filter_len: 0,
+            buckets: (0..KEY_BUCKETS).map(|_| None).collect(),
+            results: BufWriter::new(results),
+            dir,
+            len: 0,
+        })
+    }
+
+    fn bucket_index(key: &str) -> usize {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        (hasher.finish() % KEY_BUCKETS) as usize
+    }
+
+    fn bucket_path(&self, index: usize) -> PathBuf {
+        self.dir.join(format!("keys-{:03}.txt", index))
+    }
+
+    fn confirm(&mut self, index: usize, key: &str) -> Result<bool, RustFinderError> {
+        let path = self.bucket_path(index);
+        let Some(writer) = self.buckets[index].as_mut() else {
+            return Ok(false);
+        };
+        writer.flush().map_err(|e| io_error(&path, e))?;
+        let file = File::open(&path).map_err(|e| io_error(&path, e))?;
+        for line in BufReader::new(file).lines() {
+            if line.map_err(|e| io_error(&path, e))? == key {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    // Retorna true (e guarda o resultado) se a chave ainda não tinha sido vista
+    pub fn insert(&mut self, key: &str, result: &SubdomainResult) -> Result<bool, RustFinderError> {
+        let index = Self::bucket_index(key);
+        if self.filters.iter().any(|filter| filter.contains(key)) && self.confirm(index, key)? {
+            return Ok(false);
+        }
+
+        let path = self.bucket_path(index);
+        if self.buckets[index].is_none() {
+            let file = File::create(&path).map_err(|e| io_error(&path, e))?;
+            self.buckets[index] = Some(BufWriter::new(file));
+        }
+        if let Some(writer) = self.buckets[index].as_mut() {
+            writeln!(writer, "{}", key).map_err(|e| io_error(&path, e))?;
+        }
+
+        let line = serde_json::to_string(result)
+            .map_err(|e| RustFinderError::OutputError(format!("Failed to serialize result: {}", e)))?;
+        writeln!(self.results, "{}", line).map_err(|e| io_error(&self.dir, e))?;
+
+        if self.filter_len >= self.filter_capacity {
+            self.filter_capacity *= 2;
+            self.filters.push(BloomFilter::new(self.filter_capacity, FALSE_POSITIVE_RATE));
+            self.filter_len = 0;
+        }
+        if let Some(filter) = self.filters.last_mut() {
+            filter.insert(key);
+        }
+        self.filter_len += 1;
+        self.len += 1;
+        Ok(true)
+    }
+
+    pub fn unique_count(&self) -> usize {
+        self.len
+    }
+
+    pub fn memory_bytes(&self) -> usize {
+        self.filters.iter().map(BloomFilter::memory_bytes).sum()
+    }
+
+    // Lê de volta os resultados únicos, na ordem em que foram vistos, para o relatório mesclado
+    pub fn into_results(mut self) -> Result<Vec<SubdomainResult>, RustFinderError> {
+        let path = self.dir.join("results.jsonl");
+        self.results.flush().map_err(|e| io_error(&path, e))?;
+        let file = File::open(&path).map_err(|e| io_error(&path, e))?;
+        let mut results = Vec::with_capacity(self.len);
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| io_error(&path, e))?;
+            let result = serde_json::from_str(&line)
+                .map_err(|e| RustFinderError::JsonParseError(e.to_string(), line.clone()))?;
+            results.push(result);
+        }
+        Ok(results)
+    }
+}
+
+impl Drop for SpilledDedup {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bloom_filter_insert_and_contains() {
+        let mut filter = BloomFilter::new(1_000, 0.01);
+        assert!(filter.insert("a.example.com"));
+        assert!(!filter.insert("a.example.com"));
+        assert!(filter.contains("a.example.com"));
+
+        let false_positives = (0..1_000)
+            .filter(|i| filter.contains(&format!("host{}.other.org", i)))
+            .count();
+        assert!(false_positives < 50);
+    }
+
+    #[test]
+    fn test_spilled_dedup_confirms_filter_hits() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut dedup = SpilledDedup::new(dir.path().join("dedup")).unwrap();
+        let result = |name: &str| SubdomainResult {
+            subdomain: name.to_string(),
+            ..Default::default()
+        };
+
+        for i in 0..5_000 {
+            let name = format!("host{}.example.com", i);
+            assert!(dedup.insert(&name, &result(&name)).unwrap());
+        }
+        assert!(!dedup.insert("host42.example.com", &result("host42.example.com")).unwrap());
+        // Um falso positivo do filtro não pode descartar um nome novo
+        for i in 0..5_000 {
+            let name = format!("other{}.example.org", i);
+            assert!(dedup.insert(&name, &result(&name)).unwrap());
+        }
+
+        assert_eq!(dedup.unique_count(), 10_000);
+        let results = dedup.into_results().unwrap();
+        assert_eq!(results.len(), 10_000);
+        assert_eq!(results[0].subdomain, "host0.example.com");
+        assert!(!dir.path().join("dedup").exists());
+    }
+}
@@ -0,0 +1,95 @@
+// src/cache.rs
+use crate::types::{RustFinderError, SubdomainResult};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    stored_at: i64,
+    results: Vec<SubdomainResult>,
+}
+
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl ResponseCache {
+    pub fn new(dir: impl Into<PathBuf>, ttl: Duration) -> Result<Self, RustFinderError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)
+            .map_err(|e| RustFinderError::ConfigError(format!("Failed to create cache directory {:?}: {}", dir, e)))?;
+        Ok(Self { dir, ttl })
+    }
+
+    fn entry_path(&self, source_name: &str, domain: &str) -> PathBuf {
+        let sanitize = |s: &str| -> String {
+            s.chars()
+                .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+                .collect()
+        };
+        self.dir
+            .join(sanitize(source_name))
+            .join(format!("{}.json", sanitize(domain)))
+    }
+
+    // tokio::fs: chamado de dentro das tarefas de enumeração, sem bloquear o runtime
+    pub async fn get(&self, source_name: &str, domain: &str) -> Option<Vec<SubdomainResult>> {
+        let contents = tokio::fs::read_to_string(self.entry_path(source_name, domain)).await.ok()?;
+        let entry: CacheEntry = serde_json::from_str(&contents).ok()?;
+
+        let age = chrono::Utc::now().timestamp() - entry.stored_at;
+        if age < 0 || age as u64 > self.ttl.as_secs() {
+            return None;
+        }
+
+        Some(entry.results)
+    }
+
+    pub async fn put(&self, source_name: &str, domain: &str, results: &[SubdomainResult]) -> Result<(), RustFinderError> {
+        let path = self.entry_path(source_name, domain);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| RustFinderError::OutputError(format!("Failed to create cache directory: {}", e)))?;
+        }
+
+        let entry = CacheEntry {
+            stored_at: chrono::Utc::now().timestamp(),
+            results: results.to_vec(),
+        };
+        let json = serde_json::to_string(&entry)
+            .map_err(|e| RustFinderError::OutputError(format!("Failed to serialize cache entry: {}", e)))?;
+
+        tokio::fs::write(&path, json)
+            .await
+            .map_err(|e| RustFinderError::OutputError(format!("Failed to write cache entry {:?}: {}", path, e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cache_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ResponseCache::new(dir.path(), Duration::from_secs(60)).unwrap();
+
+        assert!(cache.get("crtsh", "example.com").await.is_none());
+
+        let results = vec![SubdomainResult {
+            subdomain: "www.example.com".to_string(),
+            source: "crtsh".to_string(),
+            ..Default::default()
+        }];
+        cache.put("crtsh", "example.com", &results).await.unwrap();
+
+        let cached = cache.get("crtsh", "example.com").await.unwrap();
+        assert_eq!(cached.len(), 1);
+        assert_eq!(cached[0].subdomain, "www.example.com");
+        assert!(cache.get("shodan", "example.com").await.is_none());
+    }
+}
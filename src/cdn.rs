@@ -0,0 +1,95 @@
+// src/cdn.rs
+use crate::types::RustFinderError;
+use ipnetwork::IpNetwork;
+use std::net::IpAddr;
+use std::path::Path;
+
+// Lista embutida no binário; --cdn-ranges substitui por um arquivo no mesmo formato
+const BUNDLED_RANGES: &str = include_str!("../assets/cdn_ranges.txt");
+
+#[derive(Debug, Clone, Default)]
+pub struct CdnRanges {
+    ranges: Vec<(IpNetwork, String)>,
+}
+
+impl CdnRanges {
+    pub fn bundled() -> Self {
+        Self::parse(BUNDLED_RANGES).expect("bundled CDN range list is valid")
+    }
+
+    pub fn load(path: &Path) -> Result<Self, RustFinderError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| RustFinderError::ConfigError(format!("Failed to read CDN ranges {:?}: {}", path, e)))?;
+        Self::parse(&contents)
+    }
+
+    // Uma entrada por linha: "<provedor> <cidr>"; linhas vazias e comentários (#) são ignorados
+    pub fn parse(contents: &str) -> Result<Self, RustFinderError> {
+        let mut ranges = Vec::new();
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let (Some(provider), Some(cidr), None) = (fields.next(), fields.next(), fields.next()) else {
+                return Err(RustFinderError::ConfigError(format!(
+                    "Invalid CDN range on line {}: {} (expected \"<provider> <cidr>\")",
+                    index + 1,
+                    line
+                )));
+            };
+            let network: IpNetwork = cidr.parse().map_err(|e| {
+                RustFinderError::ConfigError(format!("Invalid CIDR on line {}: {} ({})", index + 1, cidr, e))
+            })?;
+            ranges.push((network, provider.to_lowercase()));
+        }
+        Ok(Self { ranges })
+    }
+
+    // Provedor do primeiro IP que cair numa faixa conhecida
+    pub fn provider_for(&self, ip_addresses: &[String]) -> Option<&str> {
+        ip_addresses
+            .iter()
+            .filter_map(|ip| ip.parse::<IpAddr>().ok())
+            .find_map(|ip| {
+                self.ranges
+                    .iter()
+                    .find(|(network, _)| network.contains(ip))
+                    .map(|(_, provider)| provider.as_str())
+            })
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_ranges() {
+        let ranges = CdnRanges::bundled();
+        assert!(!ranges.is_empty());
+        assert_eq!(ranges.provider_for(&["104.16.1.1".to_string()]), Some("cloudflare"));
+        assert_eq!(ranges.provider_for(&["151.101.1.69".to_string()]), Some("fastly"));
+        assert_eq!(ranges.provider_for(&["2606:4700::1".to_string()]), Some("cloudflare"));
+        assert_eq!(ranges.provider_for(&["10.0.0.1".to_string()]), None);
+    }
+
+    #[test]
+    fn test_parse_ranges() {
+        let ranges = CdnRanges::parse("# comentário\n\nMyCDN 192.0.2.0/24\n").unwrap();
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(ranges.provider_for(&["bogus".to_string(), "192.0.2.10".to_string()]), Some("mycdn"));
+
+        assert!(CdnRanges::parse("mycdn").is_err());
+        assert!(CdnRanges::parse("mycdn 192.0.2.0/33").is_err());
+    }
+}
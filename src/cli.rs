@@ -1,12 +1,198 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
 #[derive(Parser, Debug, Clone)]
 #[command(
     name = "rustfinder",
     about = "Fast passive subdomain enumeration tool",
-    long_about = "RustFinder is a high-performance passive subdomain enumeration tool written in Rust.\nIt queries multiple sources to discover subdomains without directly interacting with the target."
+    long_about = "RustFinder is a high-performance passive subdomain enumeration tool written in Rust.\nIt queries multiple sources to discover subdomains without directly interacting with the target.",
+    args_conflicts_with_subcommands = true
 )]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    // Sem subcomando, as flags de topo são as de `enumerate` (compatível com a CLI antiga)
+    #[command(flatten)]
+    pub args: Args,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    #[command(about = "Enumerate subdomains from passive sources (default)")]
+    Enumerate(Args),
+
+    #[command(about = "Reverse-resolve an IP or CIDR block to hostnames via PTR lookups")]
+    Resolve(ResolveArgs),
+
+    #[command(about = "List available sources")]
+    ListSources(LogArgs),
+
+    #[command(about = "Validate the configured API keys against each source")]
+    CheckKeys(CheckKeysArgs),
+
+    #[command(about = "Check for a newer release and update the binary")]
+    Update(LogArgs),
+}
+
+impl Cli {
+    // Resolve o subcomando efetivo, traduzindo as flags antigas (--list-sources, --check-keys, --update)
+    pub fn into_command(self) -> Command {
+        match self.command.unwrap_or(Command::Enumerate(self.args)) {
+            Command::Enumerate(args) if args.list_sources => Command::ListSources(LogArgs::from_args(&args)),
+            Command::Enumerate(args) if args.check_keys => Command::CheckKeys(CheckKeysArgs {
+                log: LogArgs::from_args(&args),
+                config: ConfigArgs {
+                    config_path: args.config_path,
+                    profile: args.profile,
+                },
+            }),
+            Command::Enumerate(args) if args.update => Command::Update(LogArgs::from_args(&args)),
+            command => command,
+        }
+    }
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct ConfigArgs {
+    #[arg(short = 'c', long = "config")]
+    pub config_path: Option<String>,
+
+    #[arg(long = "profile", value_name = "NAME")]
+    pub profile: Option<String>,
+}
+
+// Flags de saída e log comuns aos subcomandos
+#[derive(clap::Args, Debug, Clone)]
+pub struct LogArgs {
+    #[arg(long = "silent")]
+    pub silent: bool,
+
+    #[arg(short = 'v', long = "verbose")]
+    pub verbose: bool,
+
+    #[arg(long = "color", value_name = "WHEN", default_value = "auto")]
+    pub color: String,
+
+    #[arg(long = "log-file", value_name = "FILE")]
+    pub log_file: Option<PathBuf>,
+
+    #[arg(long = "log-level", value_name = "LEVEL")]
+    pub log_level: Option<String>,
+
+    #[arg(long = "quiet-sources")]
+    pub quiet_sources: bool,
+}
+
+impl LogArgs {
+    // Flags antigas (--list-sources etc.) chegam junto com as demais flags de enumerate
+    fn from_args(args: &Args) -> Self {
+        Self {
+            silent: args.silent,
+            verbose: args.verbose,
+            color: args.color.clone(),
+            log_file: args.log_file.clone(),
+            log_level: args.log_level.clone(),
+            quiet_sources: args.quiet_sources,
+        }
+    }
+
+    pub fn into_args(self) -> Args {
+        Args {
+            silent: self.silent,
+            verbose: self.verbose,
+            color: self.color,
+            log_file: self.log_file,
+            log_level: self.log_level,
+            quiet_sources: self.quiet_sources,
+            ..Args::default()
+        }
+    }
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct CheckKeysArgs {
+    #[command(flatten)]
+    pub config: ConfigArgs,
+
+    #[command(flatten)]
+    pub log: LogArgs,
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct ResolveArgs {
+    #[arg(value_name = "IP_OR_CIDR")]
+    pub target: String,
+
+    // Mantém só os nomes sob estes domínios
+    #[arg(short = 'd', long = "domain", value_name = "DOMAIN")]
+    pub domain: Vec<String>,
+
+    #[arg(short = 'o', long = "output", value_name = "FILE")]
+    pub output_file: Option<String>,
+
+    #[arg(long = "json")]
+    pub json: bool,
+
+    #[arg(long = "csv")]
+    pub csv: bool,
+
+    #[arg(long = "nameserver", value_name = "IP:PORT")]
+    pub nameserver: Vec<String>,
+
+    #[arg(long = "nameserver-only", requires = "nameserver")]
+    pub nameserver_only: bool,
+
+    #[arg(long = "doh")]
+    pub doh: bool,
+
+    #[arg(long = "resolver-concurrency", value_name = "N")]
+    pub resolver_concurrency: Option<usize>,
+
+    #[arg(long = "resolver-timeout", value_name = "SECONDS")]
+    pub resolver_timeout: Option<u64>,
+
+    #[arg(long = "resolver-retries", value_name = "N")]
+    pub resolver_retries: Option<usize>,
+
+    #[arg(long = "strict-resolvers")]
+    pub strict_resolvers: bool,
+
+    #[arg(long = "rotate-nameservers")]
+    pub rotate_nameservers: bool,
+
+    #[command(flatten)]
+    pub config: ConfigArgs,
+
+    #[command(flatten)]
+    pub log: LogArgs,
+}
+
+impl ResolveArgs {
+    // O modo reverso roda pelo mesmo motor da enumeração
+    pub fn into_args(self) -> Args {
+        Args {
+            reverse: Some(self.target),
+            domain: self.domain,
+            output_file: self.output_file,
+            json: self.json,
+            csv: self.csv,
+            nameserver: self.nameserver,
+            nameserver_only: self.nameserver_only,
+            doh: self.doh,
+            resolver_concurrency: self.resolver_concurrency,
+            resolver_timeout: self.resolver_timeout,
+            resolver_retries: self.resolver_retries,
+            strict_resolvers: self.strict_resolvers,
+            rotate_nameservers: self.rotate_nameservers,
+            config_path: self.config.config_path,
+            profile: self.config.profile,
+            ..self.log.into_args()
+        }
+    }
+}
+
+#[derive(clap::Args, Debug, Clone)]
 pub struct Args {
 
     #[arg(short = 'd', long = "domain", value_name = "DOMAIN")]
@@ -15,40 +201,328 @@ pub struct Args {
     #[arg(short = 'l', long = "list", value_name = "FILE")]
     pub domains_file: Option<PathBuf>,
 
+    #[arg(long = "input-format", value_name = "FORMAT", default_value = "lines")]
+    pub input_format: String,
+
+    // Sem FILE usa a lista de TLDs embutida
+    #[arg(long = "valid-tlds", value_name = "FILE", num_args = 0..=1)]
+    pub valid_tlds: Option<Option<PathBuf>>,
+
+    #[arg(long = "list-url", value_name = "URL")]
+    pub list_url: Option<String>,
+
+    #[arg(long = "reverse", value_name = "IP_OR_CIDR", hide = true)]
+    pub reverse: Option<String>,
+
     #[arg(short = 'o', long = "output", value_name = "FILE")]
     pub output_file: Option<String>,
 
+    #[arg(long = "output-template", value_name = "TEMPLATE")]
+    pub output_template: Option<String>,
+
+    #[arg(long = "output-append")]
+    pub output_append: bool,
+
+    #[arg(long = "output-dir", value_name = "DIR")]
+    pub output_dir: Option<String>,
+
     #[arg(long = "json")]
     pub json: bool,
 
     #[arg(long = "csv")]
     pub csv: bool,
 
+    #[arg(long = "include-ip-version")]
+    pub include_ip_version: bool,
+
+    #[arg(long = "format-template", value_name = "TEMPLATE")]
+    pub format_template: Option<String>,
+
+    #[arg(long = "stats-json", value_name = "FILE")]
+    pub stats_json: Option<PathBuf>,
+
+    #[arg(long = "low-memory", requires = "merge_output")]
+    pub low_memory: bool,
+
+    #[arg(long = "on-complete", value_name = "COMMAND")]
+    pub on_complete: Option<String>,
+
+    #[arg(long = "diff", value_name = "FILE")]
+    pub diff: Option<PathBuf>,
+
+    #[arg(long = "only-new")]
+    pub only_new: bool,
+
+    #[arg(long = "summary")]
+    pub summary: bool,
+
+    #[arg(long = "sort-by", value_name = "ORDER")]
+    pub sort_by: Option<String>,
+
+    #[arg(long = "dedup-key", value_name = "KEY")]
+    pub dedup_key: Option<String>,
+
+    #[arg(long = "merge-output")]
+    pub merge_output: bool,
+
     #[arg(short = 's', long = "sources")]
     pub sources: Option<Vec<String>>,
 
+    #[arg(long = "category", value_name = "CATEGORY", value_delimiter = ',')]
+    pub category: Vec<String>,
+
+    #[arg(long = "exclude-category", value_name = "CATEGORY", value_delimiter = ',')]
+    pub exclude_category: Vec<String>,
+
+    #[arg(long = "passive-dns-only")]
+    pub passive_dns_only: bool,
+
+    #[arg(long = "only-sources-with-keys")]
+    pub only_sources_with_keys: bool,
+
+    #[arg(long = "sources-file", value_name = "FILE")]
+    pub sources_file: Option<PathBuf>,
+
     #[arg(long = "silent")]
     pub silent: bool,
 
+    #[arg(long = "plain")]
+    pub plain: bool,
+
+    #[arg(long = "stream")]
+    pub stream: bool,
+
+    #[arg(long = "color", value_name = "WHEN", default_value = "auto")]
+    pub color: String,
+
     #[arg(short = 'v', long = "verbose")]
     pub verbose: bool,
 
+    #[arg(long = "log-file", value_name = "FILE")]
+    pub log_file: Option<PathBuf>,
+
+    #[arg(long = "log-level", value_name = "LEVEL")]
+    pub log_level: Option<String>,
+
+    #[arg(long = "quiet-sources")]
+    pub quiet_sources: bool,
+
     #[arg(long = "no-resolve")]
     pub no_resolve: bool,
 
-    #[arg(long = "list-sources")]
+    #[arg(long = "records", value_name = "TYPES", value_delimiter = ',')]
+    pub records: Option<Vec<String>>,
+
+    #[arg(long = "resolver-concurrency", value_name = "N")]
+    pub resolver_concurrency: Option<usize>,
+
+    #[arg(long = "resolver-timeout", value_name = "SECONDS")]
+    pub resolver_timeout: Option<u64>,
+
+    #[arg(long = "resolver-retries", value_name = "N")]
+    pub resolver_retries: Option<usize>,
+
+    #[arg(long = "strict-resolvers")]
+    pub strict_resolvers: bool,
+
+    #[arg(long = "rotate-nameservers")]
+    pub rotate_nameservers: bool,
+
+    #[arg(long = "filter-wildcards")]
+    pub filter_wildcards: bool,
+
+    #[arg(long = "include-wildcards")]
+    pub include_wildcards: bool,
+
+    #[arg(long = "no-dns-cache")]
+    pub no_dns_cache: bool,
+
+    #[arg(long = "force-resolve")]
+    pub force_resolve: bool,
+
+    #[arg(long = "only-resolved")]
+    pub only_resolved: bool,
+
+    #[arg(long = "fail-on-unresolved", conflicts_with = "no_resolve")]
+    pub fail_on_unresolved: bool,
+
+    #[arg(long = "http-probe")]
+    pub http_probe: bool,
+
+    #[arg(long = "drop-label", value_name = "SUBSTR")]
+    pub drop_label: Vec<String>,
+
+    #[arg(long = "keep-label", value_name = "SUBSTR")]
+    pub keep_label: Vec<String>,
+
+    #[arg(long = "include-cidr", visible_alias = "include-ip", value_name = "CIDR", value_delimiter = ',')]
+    pub include_cidr: Vec<String>,
+
+    #[arg(long = "exclude-cidr", visible_alias = "exclude-ip", value_name = "CIDR", value_delimiter = ',')]
+    pub exclude_cidr: Vec<String>,
+
+    #[arg(long = "first-seen-after", value_name = "YYYY-MM-DD")]
+    pub first_seen_after: Option<String>,
+
+    #[arg(long = "require-date", requires = "first_seen_after")]
+    pub require_date: bool,
+
+    #[arg(long = "cdn-ranges", value_name = "FILE")]
+    pub cdn_ranges: Option<PathBuf>,
+
+    #[arg(long = "nameserver", value_name = "IP:PORT")]
+    pub nameserver: Vec<String>,
+
+    #[arg(long = "nameserver-only", requires = "nameserver")]
+    pub nameserver_only: bool,
+
+    #[arg(long = "doh")]
+    pub doh: bool,
+
+    #[arg(long = "proxy", value_name = "URL")]
+    pub proxy: Option<String>,
+
+    #[arg(long = "proxy-http", value_name = "URL")]
+    pub proxy_http: Option<String>,
+
+    #[arg(long = "proxy-https", value_name = "URL")]
+    pub proxy_https: Option<String>,
+
+    #[arg(long = "source-timeout", visible_alias = "timeout-per-domain", value_name = "SECONDS")]
+    pub source_timeout: Option<u64>,
+
+    #[arg(long = "key-rotation", value_name = "MODE")]
+    pub key_rotation: Option<String>,
+
+    #[arg(long = "timeout-retries", value_name = "N")]
+    pub timeout_retries: Option<u32>,
+
+    #[arg(long = "rate-limit", value_name = "N")]
+    pub rate_limit: Option<u32>,
+
+    #[arg(long = "max-pages", value_name = "N")]
+    pub max_pages: Option<u32>,
+
+    #[arg(long = "stagger-ms", value_name = "MS")]
+    pub stagger_ms: Option<u64>,
+
+    #[arg(long = "cache-dir", value_name = "DIR")]
+    pub cache_dir: Option<String>,
+
+    #[arg(long = "cache-ttl", value_name = "SECONDS")]
+    pub cache_ttl: Option<u64>,
+
+    #[arg(long = "no-cache")]
+    pub no_cache: bool,
+
+    #[arg(long = "dry-run")]
+    pub dry_run: bool,
+
+    // Substituídas pelos subcomandos; mantidas ocultas por compatibilidade
+    #[arg(long = "list-sources", hide = true)]
     pub list_sources: bool,
 
-    #[arg(long = "update")]
+    #[arg(long = "check-keys", hide = true)]
+    pub check_keys: bool,
+
+    #[arg(long = "update", hide = true)]
     pub update: bool,
 
     #[arg(short = 'c', long = "config")]
     pub config_path: Option<String>,
+
+    #[arg(long = "profile", value_name = "NAME")]
+    pub profile: Option<String>,
+}
+
+// Valores padrão do clap, como se nenhuma flag tivesse sido passada
+impl Default for Args {
+    fn default() -> Self {
+        Cli::parse_from(["rustfinder"]).args
+    }
 }
 
 impl Args {
 
     pub fn use_stdin(&self) -> bool {
-        self.domain.is_empty() && self.domains_file.is_none() && atty::is(atty::Stream::Stdin)
+        self.domain.is_empty() && self.domains_file.is_none() && self.list_url.is_none() && atty::is(atty::Stream::Stdin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_defaults_to_enumerate() {
+        let command = Cli::try_parse_from(["rustfinder", "-d", "example.com"]).unwrap().into_command();
+        assert!(matches!(command, Command::Enumerate(args) if args.domain == ["example.com"]));
+
+        let command = Cli::try_parse_from(["rustfinder", "enumerate", "-d", "example.com", "--json"])
+            .unwrap()
+            .into_command();
+        assert!(matches!(command, Command::Enumerate(args) if args.json));
+    }
+
+    #[test]
+    fn test_legacy_flags_map_to_subcommands() {
+        let command = Cli::try_parse_from(["rustfinder", "--list-sources", "--silent", "--color", "never"])
+            .unwrap()
+            .into_command();
+        assert!(matches!(command, Command::ListSources(log) if log.silent && log.color == "never"));
+
+        let command = Cli::try_parse_from(["rustfinder", "--check-keys", "-c", "keys.toml", "-v"]).unwrap().into_command();
+        assert!(matches!(command, Command::CheckKeys(keys)
+            if keys.config.config_path.as_deref() == Some("keys.toml") && keys.log.verbose));
+    }
+
+    #[test]
+    fn test_subcommands_accept_log_flags() {
+        let command = Cli::try_parse_from(["rustfinder", "update", "--log-level", "debug", "--silent"])
+            .unwrap()
+            .into_command();
+        let Command::Update(log) = command else {
+            panic!("expected update");
+        };
+        let args = log.into_args();
+        assert!(args.silent);
+        assert_eq!(args.log_level.as_deref(), Some("debug"));
+
+        let command = Cli::try_parse_from(["rustfinder", "resolve", "192.0.2.1", "--log-file", "run.log"])
+            .unwrap()
+            .into_command();
+        assert!(matches!(command, Command::Resolve(resolve) if resolve.log.log_file.is_some()));
+    }
+
+    #[test]
+    fn test_resolve_subcommand() {
+        let command = Cli::try_parse_from(["rustfinder", "resolve", "192.0.2.0/28", "-d", "example.com"])
+            .unwrap()
+            .into_command();
+        let Command::Resolve(resolve) = command else {
+            panic!("expected resolve");
+        };
+        let args = resolve.into_args();
+        assert_eq!(args.reverse.as_deref(), Some("192.0.2.0/28"));
+        assert_eq!(args.domain, ["example.com"]);
+        assert_eq!(args.input_format, "lines");
+
+        assert!(Cli::try_parse_from(["rustfinder", "-d", "example.com", "resolve", "192.0.2.1"]).is_err());
+    }
+
+    #[test]
+    fn test_fail_on_unresolved_conflicts_with_no_resolve() {
+        let command = Cli::try_parse_from(["rustfinder", "-d", "example.com", "--fail-on-unresolved"])
+            .unwrap()
+            .into_command();
+        assert!(matches!(command, Command::Enumerate(args) if args.fail_on_unresolved));
+
+        assert!(Cli::try_parse_from(["rustfinder", "-d", "example.com", "--fail-on-unresolved", "--no-resolve"]).is_err());
     }
 }
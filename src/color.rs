@@ -0,0 +1,96 @@
+// src/color.rs
+use crate::types::RustFinderError;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+impl std::str::FromStr for ColorChoice {
+    type Err = RustFinderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            other => Err(RustFinderError::ConfigError(format!(
+                "Unknown color mode: {} (expected auto, always or never)",
+                other
+            ))),
+        }
+    }
+}
+
+impl ColorChoice {
+    // No modo auto, cores só são usadas quando o fluxo de destino é um terminal e NO_COLOR não está definido
+    pub fn enabled(self, stream: atty::Stream) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                let no_color = matches!(std::env::var_os("NO_COLOR"), Some(value) if !value.is_empty());
+                !no_color && atty::is(stream)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Style {
+    enabled: bool,
+}
+
+impl Style {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+
+    pub fn plain() -> Self {
+        Self::new(false)
+    }
+
+    pub fn bold(&self, text: &str) -> String {
+        self.paint("1", text)
+    }
+
+    pub fn cyan(&self, text: &str) -> String {
+        self.paint("36", text)
+    }
+
+    pub fn green(&self, text: &str) -> String {
+        self.paint("32", text)
+    }
+
+    pub fn yellow(&self, text: &str) -> String {
+        self.paint("33", text)
+    }
+
+    pub fn dim(&self, text: &str) -> String {
+        self.paint("2", text)
+    }
+
+    fn paint(&self, code: &str, text: &str) -> String {
+        if self.enabled {
+            format!("\x1b[{}m{}\x1b[0m", code, text)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_style() {
+        assert_eq!(Style::plain().green("ok"), "ok");
+        assert_eq!(Style::new(true).green("ok"), "\x1b[32mok\x1b[0m");
+        assert_eq!("never".parse::<ColorChoice>().unwrap(), ColorChoice::Never);
+        assert!(!ColorChoice::Never.enabled(atty::Stream::Stdout));
+        assert!("rainbow".parse::<ColorChoice>().is_err());
+    }
+}
@@ -1,9 +1,33 @@
-use crate::types::{Config, RustFinderError};
+use crate::sources::SOURCE_NAMES;
+use crate::types::{Config, HttpVersion, RustFinderError, TlsVersion};
+use crate::utils;
 use std::fs;
 use std::path::Path;
 use std::env;
+use std::time::Duration;
+
+// Limites de sanidade para o cliente HTTP
+const MAX_CONNECT_TIMEOUT_SECS: u64 = 300;
+const MAX_POOL_IDLE_TIMEOUT_SECS: u64 = 3600;
+const MAX_POOL_IDLE_PER_HOST: usize = 1000;
+const MAX_RESOLVER_RETRIES: usize = 10;
+
+impl Config {
+    // Configuração apenas por variáveis de ambiente, sem arquivo (ex.: containers)
+    pub fn from_env() -> Result<Config, RustFinderError> {
+        let mut config = Config::default();
+        apply_env_overrides(&mut config)?;
+        validate_config(&config)?;
+        Ok(config)
+    }
+}
+
+// Precedência: padrões < arquivo < perfil < variáveis de ambiente
+pub fn load_config(config_path_str: &str, profile: Option<&str>) -> Result<Config, RustFinderError> {
+    if profile.is_none() && !Path::new(config_path_str).exists() {
+        return Config::from_env();
+    }
 
-pub fn load_config(config_path_str: &str) -> Result<Config, RustFinderError> {
     let mut config = Config::default();
 
     if Path::new(config_path_str).exists() {
@@ -14,19 +38,23 @@ pub fn load_config(config_path_str: &str) -> Result<Config, RustFinderError> {
             .map_err(|e| RustFinderError::ConfigError(format!("Falha ao analisar o arquivo de configuração: {}", e)))?;
 
         if let Some(table) = toml_config.as_table() {
-            if let Some(api_keys) = table.get("api_keys") {
-                if let Some(api_keys_table) = api_keys.as_table() {
-                    for (key, value) in api_keys_table {
-                        if let Some(value_array) = value.as_array() {
-                            let keys: Vec<String> = value_array.iter()
-                                .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                                .collect();
-                            config.api_keys.insert(key.clone(), keys);
-                        }
-                    }
-                }
+            apply_table(&mut config, table)?;
+
+            // O perfil é aplicado por cima da configuração base
+            if let Some(name) = profile {
+                let profile_table = table.get("profiles")
+                    .and_then(|v| v.as_table())
+                    .and_then(|profiles| profiles.get(name))
+                    .and_then(|v| v.as_table())
+                    .ok_or_else(|| RustFinderError::ConfigError(format!("Perfil não encontrado: {}", name)))?;
+                apply_table(&mut config, profile_table)?;
             }
         }
+    } else if let Some(name) = profile {
+        return Err(RustFinderError::ConfigError(format!(
+            "Perfil {} solicitado, mas o arquivo de configuração {} não existe",
+            name, config_path_str
+        )));
     }
 
     apply_env_overrides(&mut config)?;
@@ -35,9 +63,282 @@ pub fn load_config(config_path_str: &str) -> Result<Config, RustFinderError> {
     Ok(config)
 }
 
+fn apply_table(config: &mut Config, table: &toml::value::Table) -> Result<(), RustFinderError> {
+    if let Some(timeout) = table.get("timeout").and_then(|v| v.as_integer()) {
+        config.timeout = Duration::from_secs(timeout.max(0) as u64);
+    }
+    if let Some(source_timeout) = table.get("source_timeout").and_then(|v| v.as_integer()) {
+        config.source_timeout = Duration::from_secs(source_timeout.max(0) as u64);
+    }
+    if let Some(connect_timeout) = table.get("connect_timeout").and_then(|v| v.as_integer()) {
+        config.connect_timeout = Duration::from_secs(connect_timeout.max(0) as u64);
+    }
+    if let Some(pool_idle_timeout) = table.get("pool_idle_timeout").and_then(|v| v.as_integer()) {
+        config.pool_idle_timeout = Duration::from_secs(pool_idle_timeout.max(0) as u64);
+    }
+    if let Some(pool_max_idle_per_host) = table.get("pool_max_idle_per_host").and_then(|v| v.as_integer()) {
+        config.pool_max_idle_per_host = pool_max_idle_per_host.max(0) as usize;
+    }
+    if let Some(http_version) = table.get("http_version").and_then(|v| v.as_str()) {
+        config.http_version = http_version.parse()?;
+    }
+    if let Some(min_tls_version) = table.get("min_tls_version").and_then(|v| v.as_str()) {
+        config.min_tls_version = match min_tls_version {
+            "" | "auto" => None,
+            version => Some(version.parse()?),
+        };
+    }
+    if let Some(user_agent) = table.get("user_agent").and_then(|v| v.as_str()) {
+        config.user_agent = user_agent.to_string();
+    }
+    if let Some(proxy) = table.get("proxy").and_then(|v| v.as_str()) {
+        config.proxy = Some(proxy.to_string());
+    }
+    if let Some(proxy_http) = table.get("proxy_http").and_then(|v| v.as_str()) {
+        config.proxy_http = Some(proxy_http.to_string());
+    }
+    if let Some(proxy_https) = table.get("proxy_https").and_then(|v| v.as_str()) {
+        config.proxy_https = Some(proxy_https.to_string());
+    }
+    if let Some(cache_dir) = table.get("cache_dir").and_then(|v| v.as_str()) {
+        config.cache_dir = Some(cache_dir.to_string());
+    }
+    if let Some(cache_ttl) = table.get("cache_ttl").and_then(|v| v.as_integer()) {
+        config.cache_ttl = Duration::from_secs(cache_ttl.max(0) as u64);
+    }
+    if let Some(retry_attempts) = table.get("retry_attempts").and_then(|v| v.as_integer()) {
+        config.retry_attempts = retry_attempts.clamp(0, u32::MAX as i64) as u32;
+    }
+    if let Some(retry_delay_ms) = table.get("retry_delay_ms").and_then(|v| v.as_integer()) {
+        config.retry_delay_ms = retry_delay_ms.max(0) as u64;
+    }
+    if let Some(retry_backoff) = table.get("retry_backoff").and_then(|v| v.as_str()) {
+        config.retry_backoff = retry_backoff.parse()?;
+    }
+    if let Some(global_rate_limit) = table.get("global_rate_limit").and_then(|v| v.as_integer()) {
+        // 0 desativa o limite global
+        config.global_rate_limit = match global_rate_limit.clamp(0, u32::MAX as i64) as u32 {
+            0 => None,
+            limit => Some(limit),
+        };
+    }
+    if let Some(include_wildcards) = table.get("include_wildcards").and_then(|v| v.as_bool()) {
+        config.include_wildcards = include_wildcards;
+    }
+    if let Some(max_pages) = table.get("max_pages").and_then(|v| v.as_integer()) {
+        config.max_pages = max_pages.clamp(0, u32::MAX as i64) as u32;
+    }
+    if let Some(key_rotation) = table.get("key_rotation").and_then(|v| v.as_str()) {
+        config.key_rotation = key_rotation.parse()?;
+    }
+    if let Some(dedup_key) = table.get("dedup_key").and_then(|v| v.as_str()) {
+        config.dedup_key = dedup_key.parse()?;
+    }
+    if let Some(stagger_ms) = table.get("stagger_ms").and_then(|v| v.as_integer()) {
+        config.stagger_ms = stagger_ms.max(0) as u64;
+    }
+    if let Some(timeout_retries) = table.get("timeout_retries").and_then(|v| v.as_integer()) {
+        config.timeout_retries = timeout_retries.clamp(0, u32::MAX as i64) as u32;
+    }
+    if let Some(max_retry_delay_ms) = table.get("max_retry_delay_ms").and_then(|v| v.as_integer()) {
+        config.max_retry_delay_ms = max_retry_delay_ms.max(0) as u64;
+    }
+    if let Some(max_response_bytes) = table.get("max_response_bytes").and_then(|v| v.as_integer()) {
+        config.max_response_bytes = max_response_bytes.max(0) as u64;
+    }
+    if let Some(crtsh_timeout) = table.get("crtsh_timeout").and_then(|v| v.as_integer()) {
+        config.crtsh_timeout = Duration::from_secs(crtsh_timeout.max(0) as u64);
+    }
+    if let Some(crtsh_max_response_bytes) = table.get("crtsh_max_response_bytes").and_then(|v| v.as_integer()) {
+        config.crtsh_max_response_bytes = crtsh_max_response_bytes.max(0) as u64;
+    }
+
+    if let Some(sources) = table.get("sources").and_then(|v| v.as_array()) {
+        config.sources = sources.iter()
+            .filter_map(|v| v.as_str().map(|s| s.trim().to_lowercase()))
+            .filter(|s| !s.is_empty())
+            .collect();
+    }
+
+    if let Some(source_priority) = table.get("source_priority").and_then(|v| v.as_array()) {
+        config.source_priority = source_priority.iter()
+            .filter_map(|v| v.as_str().map(|s| s.to_lowercase()))
+            .collect();
+    }
+
+    if let Some(api_keys) = table.get("api_keys") {
+        if let Some(api_keys_table) = api_keys.as_table() {
+            for (key, value) in api_keys_table {
+                if let Some(value_array) = value.as_array() {
+                    let keys: Vec<String> = value_array.iter()
+                        .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                        .collect();
+                    config.api_keys.insert(key.clone(), keys);
+                }
+            }
+        }
+    }
+
+    if let Some(rate_limits) = table.get("rate_limits").and_then(|v| v.as_table()) {
+        for (source, value) in rate_limits {
+            if let Some(limit) = value.as_integer() {
+                // 0 desativa o limite da fonte
+                let limit = limit.clamp(0, u32::MAX as i64) as u32;
+                config.rate_limits.insert(source.to_lowercase(), Some(limit).filter(|l| *l > 0));
+            }
+        }
+    }
+
+    if let Some(base_urls) = table.get("base_urls").and_then(|v| v.as_table()) {
+        for (source, value) in base_urls {
+            if let Some(url) = value.as_str() {
+                config.base_urls.insert(source.to_lowercase(), url.to_string());
+            }
+        }
+    }
+
+    if let Some(output) = table.get("output").and_then(|v| v.as_table()) {
+        if let Some(format) = output.get("format").and_then(|v| v.as_str()) {
+            config.output.format = format.parse()?;
+        }
+        if let Some(sort_by) = output.get("sort_by").and_then(|v| v.as_str()) {
+            config.output.sort_by = sort_by.parse()?;
+        }
+        if let Some(verbose) = output.get("verbose").and_then(|v| v.as_bool()) {
+            config.output.verbose = verbose;
+        }
+        if let Some(include_ips) = output.get("include_ips").and_then(|v| v.as_bool()) {
+            config.output.include_ips = include_ips;
+        }
+        if let Some(include_ip_version) = output.get("include_ip_version").and_then(|v| v.as_bool()) {
+            config.output.include_ip_version = include_ip_version;
+        }
+        if let Some(summary) = output.get("summary").and_then(|v| v.as_bool()) {
+            config.output.summary = summary;
+        }
+        if let Some(plain) = output.get("plain").and_then(|v| v.as_bool()) {
+            config.output.plain = plain;
+        }
+    }
+
+    if let Some(resolver) = table.get("resolver").and_then(|v| v.as_table()) {
+        if let Some(enabled) = resolver.get("enabled").and_then(|v| v.as_bool()) {
+            config.resolver.enabled = enabled;
+        }
+        if let Some(threads) = resolver.get("threads").and_then(|v| v.as_integer()) {
+            config.resolver.threads = threads.max(0) as usize;
+        }
+        if let Some(timeout) = resolver.get("timeout").and_then(|v| v.as_integer()) {
+            config.resolver.timeout = Duration::from_secs(timeout.max(0) as u64);
+        }
+        if let Some(cache) = resolver.get("cache").and_then(|v| v.as_bool()) {
+            config.resolver.cache = cache;
+        }
+        if let Some(force_resolve) = resolver.get("force_resolve").and_then(|v| v.as_bool()) {
+            config.resolver.force_resolve = force_resolve;
+        }
+        if let Some(protocol) = resolver.get("protocol").and_then(|v| v.as_str()) {
+            config.resolver.protocol = protocol.parse()?;
+        }
+        if let Some(tls_dns_name) = resolver.get("tls_dns_name").and_then(|v| v.as_str()) {
+            config.resolver.tls_dns_name = Some(tls_dns_name.to_string());
+        }
+        if let Some(records) = resolver.get("records").and_then(|v| v.as_array()) {
+            config.resolver.records = records.iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_uppercase()))
+                .collect();
+        }
+        if let Some(retries) = resolver.get("retries").and_then(|v| v.as_integer()) {
+            config.resolver.retries = retries.max(0) as usize;
+        }
+        if let Some(probe_nameservers) = resolver.get("probe_nameservers").and_then(|v| v.as_bool()) {
+            config.resolver.probe_nameservers = probe_nameservers;
+        }
+        if let Some(strict) = resolver.get("strict").and_then(|v| v.as_bool()) {
+            config.resolver.strict = strict;
+        }
+        if let Some(rotate) = resolver.get("rotate_nameservers").and_then(|v| v.as_bool()) {
+            config.resolver.rotate_nameservers = rotate;
+        }
+        if let Some(filter_wildcards) = resolver.get("filter_wildcards").and_then(|v| v.as_bool()) {
+            config.resolver.filter_wildcards = filter_wildcards;
+        }
+        if let Some(probes) = resolver.get("wildcard_probes").and_then(|v| v.as_array()) {
+            config.resolver.wildcard_probes = probes.iter()
+                .filter_map(|v| v.as_str().map(|s| s.trim().to_lowercase()))
+                .collect();
+        }
+        if let Some(nameservers) = resolver.get("nameservers").and_then(|v| v.as_array()) {
+            config.resolver.nameservers = nameservers.iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect();
+        }
+    }
+    Ok(())
+}
+
 fn apply_env_overrides(config: &mut Config) -> Result<(), RustFinderError> {
-    if let Ok(keys) = env::var("VIRUSTOTAL_API_KEYS") {
-        config.api_keys.insert("virustotal".to_string(), keys.split(',').map(|s| s.trim().to_string()).collect());
+    apply_env_from(config, |name| env::var(name).ok())
+}
+
+// Separado de apply_env_overrides para que os testes não dependam do ambiente do processo
+fn apply_env_from<F>(config: &mut Config, lookup: F) -> Result<(), RustFinderError>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let var = |name: &str| lookup(name).map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+    let list = |value: String| -> Vec<String> {
+        value.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+    };
+    let number = |name: &str, value: String| -> Result<u64, RustFinderError> {
+        value.parse().map_err(|_| {
+            RustFinderError::ConfigError(format!("Valor inválido em {}: {}", name, value))
+        })
+    };
+
+    // Nome legado, mantido por compatibilidade
+    if let Some(keys) = var("VIRUSTOTAL_API_KEYS") {
+        config.api_keys.insert("virustotal".to_string(), list(keys));
+    }
+
+    for source in SOURCE_NAMES {
+        let prefix = source.to_uppercase();
+        if let Some(keys) = var(&format!("RUSTFINDER_{}_API_KEYS", prefix)) {
+            config.api_keys.insert(source.to_string(), list(keys));
+        }
+        let name = format!("RUSTFINDER_RATE_LIMIT_{}", prefix);
+        if let Some(limit) = var(&name) {
+            let limit = u32::try_from(number(&name, limit.clone())?).map_err(|_| {
+                RustFinderError::ConfigError(format!("Valor inválido em {}: {}", name, limit))
+            })?;
+            // 0 desativa o limite da fonte
+            config.rate_limits.insert(source.to_string(), Some(limit).filter(|l| *l > 0));
+        }
+    }
+
+    if let Some(timeout) = var("RUSTFINDER_TIMEOUT") {
+        config.timeout = Duration::from_secs(number("RUSTFINDER_TIMEOUT", timeout)?);
+    }
+    if let Some(source_timeout) = var("RUSTFINDER_SOURCE_TIMEOUT") {
+        config.source_timeout = Duration::from_secs(number("RUSTFINDER_SOURCE_TIMEOUT", source_timeout)?);
+    }
+    if let Some(proxy) = var("RUSTFINDER_PROXY") {
+        config.proxy = Some(proxy);
+    }
+    if let Some(proxy_http) = var("RUSTFINDER_PROXY_HTTP") {
+        config.proxy_http = Some(proxy_http);
+    }
+    if let Some(proxy_https) = var("RUSTFINDER_PROXY_HTTPS") {
+        config.proxy_https = Some(proxy_https);
+    }
+    if let Some(sources) = var("RUSTFINDER_SOURCES") {
+        config.sources = list(sources).into_iter().map(|s| s.to_lowercase()).collect();
+    }
+    if let Some(cache_dir) = var("RUSTFINDER_CACHE_DIR") {
+        config.cache_dir = Some(cache_dir);
+    }
+    if let Some(nameservers) = var("RUSTFINDER_NAMESERVERS") {
+        config.resolver.nameservers = list(nameservers);
     }
     Ok(())
 }
@@ -46,8 +347,208 @@ fn validate_config(config: &Config) -> Result<(), RustFinderError> {
     if config.timeout.as_secs() == 0 {
         return Err(RustFinderError::ConfigError("O timeout deve ser maior que 0".to_string()));
     }
+    if !(1..=MAX_CONNECT_TIMEOUT_SECS).contains(&config.connect_timeout.as_secs()) {
+        return Err(RustFinderError::ConfigError(format!(
+            "connect_timeout deve estar entre 1 e {} segundos",
+            MAX_CONNECT_TIMEOUT_SECS
+        )));
+    }
+    if config.pool_idle_timeout.as_secs() > MAX_POOL_IDLE_TIMEOUT_SECS {
+        return Err(RustFinderError::ConfigError(format!(
+            "pool_idle_timeout deve ser no máximo {} segundos",
+            MAX_POOL_IDLE_TIMEOUT_SECS
+        )));
+    }
+    if config.pool_max_idle_per_host > MAX_POOL_IDLE_PER_HOST {
+        return Err(RustFinderError::ConfigError(format!(
+            "pool_max_idle_per_host deve ser no máximo {}",
+            MAX_POOL_IDLE_PER_HOST
+        )));
+    }
+    // O backend TLS nativo do reqwest não aceita exigir TLS 1.3 como mínimo
+    if config.min_tls_version == Some(TlsVersion::Tls1_3) {
+        return Err(RustFinderError::ConfigError(
+            "min_tls_version = \"1.3\" não é suportado pelo backend TLS nativo; use até 1.2".to_string(),
+        ));
+    }
+    // Proxy HTTP fala HTTP/1.1; HTTP/2 direto só funciona sem proxy ou com SOCKS
+    if config.http_version == HttpVersion::Http2 {
+        let http_proxy = [&config.proxy, &config.proxy_http, &config.proxy_https]
+            .into_iter()
+            .flatten()
+            .find(|p| p.starts_with("http://") || p.starts_with("https://"));
+        if let Some(proxy) = http_proxy {
+            return Err(RustFinderError::ConfigError(format!(
+                "http_version = \"2\" não é compatível com o proxy HTTP {}; use SOCKS ou http_version = \"auto\"",
+                proxy
+            )));
+        }
+    }
+    if config.max_response_bytes == 0 {
+        return Err(RustFinderError::ConfigError("O tamanho máximo de resposta deve ser maior que 0".to_string()));
+    }
+    if config.crtsh_timeout.as_secs() == 0 || config.crtsh_max_response_bytes == 0 {
+        return Err(RustFinderError::ConfigError("Os limites do crt.sh devem ser maiores que 0".to_string()));
+    }
+    if config.max_pages == 0 {
+        return Err(RustFinderError::ConfigError("O número máximo de páginas deve ser maior que 0".to_string()));
+    }
+    if config.source_timeout.as_secs() == 0 {
+        return Err(RustFinderError::ConfigError("O timeout por fonte deve ser maior que 0".to_string()));
+    }
+    if let Some(label) = config.resolver.wildcard_probes.iter().find(|l| !utils::is_valid_dns_label(l)) {
+        return Err(RustFinderError::ConfigError(format!("Rótulo inválido em resolver.wildcard_probes: {}", label)));
+    }
+    if config.resolver.timeout.as_secs() == 0 {
+        return Err(RustFinderError::ConfigError("O timeout do resolvedor deve ser maior que 0".to_string()));
+    }
     if config.resolver.threads == 0 {
         return Err(RustFinderError::ConfigError("As threads do resolvedor devem ser maiores que 0".to_string()));
     }
+    if config.resolver.retries > MAX_RESOLVER_RETRIES {
+        return Err(RustFinderError::ConfigError(format!(
+            "resolver.retries deve ser no máximo {}",
+            MAX_RESOLVER_RETRIES
+        )));
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BackoffStrategy;
+
+    #[test]
+    fn test_env_overrides() {
+        let vars: std::collections::HashMap<&str, &str> = [
+            ("RUSTFINDER_SHODAN_API_KEYS", "k1, k2"),
+            ("RUSTFINDER_TIMEOUT", "45"),
+            ("RUSTFINDER_RATE_LIMIT_CRTSH", "2"),
+            ("RUSTFINDER_RATE_LIMIT_VIRUSTOTAL", "0"),
+            ("RUSTFINDER_PROXY", "socks5://127.0.0.1:9050"),
+        ]
+        .into_iter()
+        .collect();
+
+        let mut config = Config::default();
+        apply_env_from(&mut config, |name| vars.get(name).map(|v| v.to_string())).unwrap();
+
+        assert_eq!(config.api_keys["shodan"], vec!["k1", "k2"]);
+        assert_eq!(config.timeout, Duration::from_secs(45));
+        assert_eq!(config.rate_limits["crtsh"], Some(2));
+        assert_eq!(config.rate_limits["virustotal"], None);
+        assert_eq!(config.proxy.as_deref(), Some("socks5://127.0.0.1:9050"));
+
+        let mut config = Config::default();
+        let result = apply_env_from(&mut config, |name| (name == "RUSTFINDER_TIMEOUT").then(|| "soon".to_string()));
+        assert!(result.is_err());
+
+        let mut config = Config::default();
+        let result = apply_env_from(&mut config, |name| {
+            (name == "RUSTFINDER_RATE_LIMIT_CRTSH").then(|| "4294967296".to_string())
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_profile_overrides_base() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "timeout = 20\nstagger_ms = 0\n\n[profiles.stealth]\nstagger_ms = 2000\n",
+        )
+        .unwrap();
+        let path = path.to_str().unwrap();
+
+        let base = load_config(path, None).unwrap();
+        assert_eq!(base.stagger_ms, 0);
+
+        let stealth = load_config(path, Some("stealth")).unwrap();
+        assert_eq!(stealth.stagger_ms, 2000);
+        assert_eq!(stealth.timeout, Duration::from_secs(20));
+
+        assert!(load_config(path, Some("missing")).is_err());
+    }
+
+    #[test]
+    fn test_profile_overrides_retry_and_rate_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(
+            &path,
+            concat!(
+                "retry_delay_ms = 500\n",
+                "[rate_limits]\ncrtsh = 5\n\n",
+                "[profiles.stealth]\n",
+                "sources = [\"crtsh\", \"hackertarget\"]\n",
+                "retry_delay_ms = 5000\n",
+                "user_agent = \"Mozilla/5.0\"\n",
+                "[profiles.stealth.rate_limits]\ncrtsh = 1\nshodan = 0\n",
+                "[profiles.stealth.resolver]\nthreads = 5\n",
+                "[profiles.stealth.output]\nformat = \"json\"\n",
+            ),
+        )
+        .unwrap();
+        let path = path.to_str().unwrap();
+
+        let base = load_config(path, None).unwrap();
+        assert_eq!(base.retry_delay_ms, 500);
+        assert_eq!(base.rate_limits["crtsh"], Some(5));
+        assert_eq!(base.rate_limits["shodan"], Some(1));
+
+        let stealth = load_config(path, Some("stealth")).unwrap();
+        assert_eq!(stealth.sources, vec!["crtsh", "hackertarget"]);
+        assert_eq!(stealth.retry_delay_ms, 5000);
+        assert_eq!(stealth.user_agent, "Mozilla/5.0");
+        assert_eq!(stealth.rate_limits["crtsh"], Some(1));
+        assert_eq!(stealth.rate_limits["shodan"], None);
+        assert_eq!(stealth.resolver.threads, 5);
+        assert_eq!(stealth.output.format, crate::types::OutputFormat::Json);
+    }
+
+    #[test]
+    fn test_http_client_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "connect_timeout = 5\npool_idle_timeout = 30\npool_max_idle_per_host = 4\n").unwrap();
+
+        let config = load_config(path.to_str().unwrap(), None).unwrap();
+        assert_eq!(config.connect_timeout, Duration::from_secs(5));
+        assert_eq!(config.pool_idle_timeout, Duration::from_secs(30));
+        assert_eq!(config.pool_max_idle_per_host, 4);
+
+        fs::write(&path, "retry_attempts = 5\nretry_delay_ms = 2000\nretry_backoff = \"constant\"\n").unwrap();
+        let config = load_config(path.to_str().unwrap(), None).unwrap();
+        assert_eq!(config.retry_attempts, 5);
+        assert_eq!(config.retry_delay_ms, 2000);
+        assert_eq!(config.retry_backoff, BackoffStrategy::Constant);
+
+        fs::write(&path, "connect_timeout = 0\n").unwrap();
+        assert!(load_config(path.to_str().unwrap(), None).is_err());
+        fs::write(&path, "pool_max_idle_per_host = 100000\n").unwrap();
+        assert!(load_config(path.to_str().unwrap(), None).is_err());
+    }
+
+    #[test]
+    fn test_http_version_and_tls() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let path_str = path.to_str().unwrap();
+
+        fs::write(&path, "http_version = \"2\"\nmin_tls_version = \"1.2\"\n").unwrap();
+        let config = load_config(path_str, None).unwrap();
+        assert_eq!(config.http_version, HttpVersion::Http2);
+        assert_eq!(config.min_tls_version, Some(TlsVersion::Tls1_2));
+
+        fs::write(&path, "min_tls_version = \"1.3\"\n").unwrap();
+        assert!(load_config(path_str, None).is_err());
+        fs::write(&path, "http_version = \"3\"\n").unwrap();
+        assert!(load_config(path_str, None).is_err());
+        fs::write(&path, "http_version = \"2\"\nproxy = \"http://127.0.0.1:8080\"\n").unwrap();
+        assert!(load_config(path_str, None).is_err());
+        fs::write(&path, "http_version = \"2\"\nproxy = \"socks5://127.0.0.1:9050\"\n").unwrap();
+        assert!(load_config(path_str, None).is_ok());
+    }
+}
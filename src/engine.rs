@@ -1,69 +1,406 @@
+use crate::bloom::SpilledDedup;
+use crate::cache::ResponseCache;
+use crate::cdn::CdnRanges;
 use crate::cli::Args;
+use crate::color::ColorChoice;
 use crate::config;
-use crate::output::OutputManager;
-use crate::resolver::Resolver;
+use crate::output::{load_known_subdomains, write_stats_json, FormatTemplate, OutputManager, ReportWriter};
+use crate::resolver::{DnsResolver, Resolver, DEFAULT_DOH_NAMESERVERS, DEFAULT_DOH_TLS_NAME};
 use crate::session::Session;
-use crate::sources::{create_source, get_all_sources, Source};
-use crate::types::{Config, DomainReport, EnumerationStats, RustFinderError, SubdomainResult};
+use crate::sources::{create_source, get_all_sources, Source, SourceRegistry, STREAM_BUFFER};
+use crate::utils;
+use crate::types::{
+    Config, DedupKey, DomainReport, EnumerationStats, ResolutionStatus, ResolverConfig, ResolverProtocol, RunStats,
+    RustFinderError, SourceCategory, SourceError, SourceStats,
+    SubdomainResult,
+};
 use futures::stream::{FuturesUnordered, StreamExt};
+use futures::FutureExt;
+use ipnetwork::IpNetwork;
 use log::{debug, error, info, warn};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::net::SocketAddr;
+use std::panic::AssertUnwindSafe;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::Mutex;
 use tokio::time::{timeout, Duration as TokioDuration};
 
+const HTTP_PROBE_CONCURRENCY: usize = 20;
+const MAX_REVERSE_HOSTS: u128 = 65_536;
+const WILDCARD_PROBE_COUNT: usize = 3;
+// Nomes citados na mensagem de --fail-on-unresolved
+const UNRESOLVED_SAMPLE: usize = 5;
+
+// Saída de --stream: os filtros de rótulo valem antes de exibir, e cada nome aparece uma vez
+// mesmo quando várias fontes o encontram
+struct StreamPrinter {
+    printed: std::sync::Mutex<HashSet<String>>,
+    plain: bool,
+    drop_label: Vec<String>,
+    keep_label: Vec<String>,
+}
+
+impl StreamPrinter {
+    fn print(&self, name: &str, source: &str) {
+        if !utils::matches_label_filter(name, &self.drop_label, &self.keep_label) {
+            return;
+        }
+        let first = self.printed.lock().map(|mut printed| printed.insert(name.to_string())).unwrap_or(false);
+        if !first {
+            return;
+        }
+        if self.plain {
+            println!("{}", name);
+        } else {
+            println!("{} [{}]", name, source);
+        }
+    }
+}
+
 pub struct RustFinderEngine {
     config: Config,
     session: Session,
     sources: Vec<Box<dyn Source>>,
-    resolver: Option<Arc<Resolver>>,
+    resolver: Option<Arc<dyn DnsResolver>>,
+    cache: Option<Arc<ResponseCache>>,
+    include_cidrs: Vec<IpNetwork>,
+    exclude_cidrs: Vec<IpNetwork>,
+    cdn_ranges: CdnRanges,
+    first_seen_after: Option<chrono::NaiveDate>,
     output_manager: OutputManager,
     args: Args,
+    run_id: String,
+    // Resultados de enumerate_batch por fonte e domínio (ASCII), consumidos por enumerate_domain_internal
+    batch_results: HashMap<String, HashMap<String, Vec<SubdomainResult>>>,
+    // Domínios reprovados por --fail-on-unresolved, com a contagem de nomes sem resposta
+    unresolved_failures: Vec<String>,
+    interrupted: Arc<AtomicBool>,
+    // run e run_reverse instalam o tratador de Ctrl-C uma única vez por engine
+    interrupt_handler: std::sync::Once,
 }
 
 impl RustFinderEngine {
     pub async fn new(args: Args, config_path_str: &str) -> Result<Self, RustFinderError> {
-        let mut config = config::load_config(config_path_str)?;
+        Self::with_registry(args, config_path_str, SourceRegistry::new()).await
+    }
+
+    pub async fn with_registry(
+        mut args: Args,
+        config_path_str: &str,
+        registry: SourceRegistry,
+    ) -> Result<Self, RustFinderError> {
+        let mut config = config::load_config(config_path_str, args.profile.as_deref())?;
+
+        if let Some(sources_file) = args.sources_file.clone() {
+            let names = utils::read_list_file(&sources_file).map_err(|e| {
+                RustFinderError::ConfigError(format!("Falha ao ler arquivo de fontes {:?}: {}", sources_file, e))
+            })?;
+            let sources = args.sources.get_or_insert_with(Vec::new);
+            for name in names {
+                if !sources.iter().any(|s| s.eq_ignore_ascii_case(&name)) {
+                    sources.push(name);
+                }
+            }
+        }
 
         if let Some(output_file_val) = args.output_file.clone() {
             config.output.file = Some(output_file_val);
         }
+        if let Some(output_dir) = args.output_dir.clone() {
+            if args.output_file.is_some() {
+                return Err(RustFinderError::ConfigError(
+                    "Use apenas um de -o/--output e --output-dir".to_string(),
+                ));
+            }
+            config.output.dir = Some(output_dir);
+        }
+        if let Some(path_template) = args.output_template.clone() {
+            if args.output_file.is_some() || args.output_dir.is_some() {
+                return Err(RustFinderError::ConfigError(
+                    "Use apenas um de -o/--output, --output-dir e --output-template".to_string(),
+                ));
+            }
+            crate::output::validate_path_template(&path_template)?;
+            config.output.path_template = Some(path_template);
+        }
+        if args.output_append {
+            if args.output_file.is_none() && args.output_dir.is_none() && args.output_template.is_none() {
+                return Err(RustFinderError::ConfigError(
+                    "--output-append requer -o/--output, --output-dir ou --output-template".to_string(),
+                ));
+            }
+            config.output.append = true;
+        }
         if args.verbose {
             config.output.verbose = true;
         }
+        config.output.color = args.color.parse::<ColorChoice>()?.enabled(atty::Stream::Stdout);
         if args.json {
             config.output.format = crate::types::OutputFormat::Json;
         }
         if args.csv {
             config.output.format = crate::types::OutputFormat::Csv;
         }
+        if config.output.append && config.output.format == crate::types::OutputFormat::Json {
+            warn!("[Engine] --output-append com --json grava JSON Lines (um relatório compacto por linha)");
+        }
+        if let Some(sort_by) = &args.sort_by {
+            config.output.sort_by = sort_by.parse()?;
+        }
+        if args.include_ip_version {
+            config.output.include_ip_version = true;
+        }
+        if args.summary {
+            config.output.summary = true;
+        }
+        if args.plain {
+            config.output.plain = true;
+        }
+        if let Some(dedup_key) = &args.dedup_key {
+            config.dedup_key = dedup_key.parse()?;
+        }
+        if let Some(template) = args.format_template.clone() {
+            config.output.template = Some(template);
+        }
         if args.no_resolve {
             config.resolver.enabled = false;
         }
+        if let Some(concurrency) = args.resolver_concurrency {
+            if concurrency == 0 {
+                return Err(RustFinderError::ConfigError(
+                    "--resolver-concurrency deve ser maior que 0".to_string(),
+                ));
+            }
+            config.resolver.threads = concurrency;
+        }
+        if let Some(resolver_timeout) = args.resolver_timeout {
+            if resolver_timeout == 0 {
+                return Err(RustFinderError::ConfigError(
+                    "--resolver-timeout deve ser maior que 0".to_string(),
+                ));
+            }
+            config.resolver.timeout = std::time::Duration::from_secs(resolver_timeout);
+        }
+        if let Some(resolver_retries) = args.resolver_retries {
+            config.resolver.retries = resolver_retries;
+        }
+        if args.strict_resolvers {
+            config.resolver.probe_nameservers = true;
+            config.resolver.strict = true;
+        }
+        if args.rotate_nameservers {
+            config.resolver.rotate_nameservers = true;
+        }
+        if args.force_resolve {
+            config.resolver.force_resolve = true;
+        }
+        if args.no_dns_cache {
+            config.resolver.cache = false;
+        }
+        if args.filter_wildcards {
+            config.resolver.filter_wildcards = true;
+        }
+        if args.include_wildcards {
+            config.include_wildcards = true;
+        }
+        if let Some(records) = args.records.clone() {
+            config.resolver.records = records.iter().map(|r| r.trim().to_uppercase()).collect();
+        }
+        if args.only_new {
+            if args.diff.is_none() {
+                return Err(RustFinderError::ConfigError(
+                    "--only-new requer --diff <relatório-anterior.json>".to_string(),
+                ));
+            }
+            config.output.only_new = true;
+        }
+        if let Some(source_timeout) = args.source_timeout {
+            if source_timeout == 0 {
+                return Err(RustFinderError::ConfigError(
+                    "--source-timeout deve ser maior que 0".to_string(),
+                ));
+            }
+            config.source_timeout = std::time::Duration::from_secs(source_timeout);
+        }
+        if let Some(key_rotation) = &args.key_rotation {
+            config.key_rotation = key_rotation.parse()?;
+        }
+        if let Some(timeout_retries) = args.timeout_retries {
+            config.timeout_retries = timeout_retries;
+        }
+        if let Some(rate_limit) = args.rate_limit {
+            if rate_limit == 0 {
+                return Err(RustFinderError::ConfigError(
+                    "--rate-limit deve ser maior que 0".to_string(),
+                ));
+            }
+            config.global_rate_limit = Some(rate_limit);
+        }
+        if let Some(max_pages) = args.max_pages {
+            if max_pages == 0 {
+                return Err(RustFinderError::ConfigError(
+                    "--max-pages deve ser maior que 0".to_string(),
+                ));
+            }
+            config.max_pages = max_pages;
+        }
+        if let Some(stagger_ms) = args.stagger_ms {
+            config.stagger_ms = stagger_ms;
+        }
+        if let Some(cache_dir) = args.cache_dir.clone() {
+            config.cache_dir = Some(cache_dir);
+        }
+        if let Some(cache_ttl) = args.cache_ttl {
+            config.cache_ttl = std::time::Duration::from_secs(cache_ttl);
+        }
+        if args.no_cache {
+            config.cache_dir = None;
+        }
+        if let Some(proxy) = args.proxy.clone() {
+            config.proxy = Some(proxy);
+        }
+        if let Some(proxy_http) = args.proxy_http.clone() {
+            config.proxy_http = Some(proxy_http);
+        }
+        if let Some(proxy_https) = args.proxy_https.clone() {
+            config.proxy_https = Some(proxy_https);
+        }
+        if args.doh {
+            config.resolver.protocol = ResolverProtocol::Https;
+            config.resolver.use_system_resolver = false;
+            config.resolver.nameservers = DEFAULT_DOH_NAMESERVERS.iter().map(|s| s.to_string()).collect();
+            config.resolver.tls_dns_name = Some(DEFAULT_DOH_TLS_NAME.to_string());
+        }
+        if !args.nameserver.is_empty() {
+            for nameserver in &args.nameserver {
+                SocketAddr::from_str(nameserver.trim()).map_err(|_| {
+                    RustFinderError::ConfigError(format!(
+                        "Nameserver inválido: {} (esperado ip:porta, ex.: 10.0.0.53:53 ou [2001:db8::53]:53)",
+                        nameserver
+                    ))
+                })?;
+            }
+            if args.nameserver_only {
+                config.resolver.nameservers.clear();
+            }
+            for nameserver in &args.nameserver {
+                let nameserver = nameserver.trim().to_string();
+                if !config.resolver.nameservers.contains(&nameserver) {
+                    config.resolver.nameservers.push(nameserver);
+                }
+            }
+            config.resolver.use_system_resolver = false;
+        }
+
+        if (!args.include_cidr.is_empty() || !args.exclude_cidr.is_empty()) && !config.resolver.enabled {
+            return Err(RustFinderError::ConfigError(
+                "--include-cidr/--exclude-cidr requerem a resolução DNS habilitada (remova --no-resolve)".to_string(),
+            ));
+        }
+
+        if args.only_resolved && !config.resolver.enabled {
+            return Err(RustFinderError::ConfigError(
+                "--only-resolved requer a resolução DNS habilitada (remova --no-resolve)".to_string(),
+            ));
+        }
+
+        // Os nomes de --stream saem antes da resolução e dos filtros que dependem dela
+        if args.stream {
+            let conflict = [
+                (args.only_resolved, "--only-resolved"),
+                (!args.include_cidr.is_empty() || !args.exclude_cidr.is_empty(), "--include-cidr/--exclude-cidr"),
+                (args.first_seen_after.is_some(), "--first-seen-after"),
+                (config.resolver.filter_wildcards, "--filter-wildcards"),
+            ]
+            .into_iter()
+            .find(|(set, _)| *set);
+            if let Some((_, flag)) = conflict {
+                return Err(RustFinderError::ConfigError(format!(
+                    "--stream exibe os nomes antes de filtrar e não pode ser combinado com {}",
+                    flag
+                )));
+            }
+        }
+
+        if args.fail_on_unresolved && !config.resolver.enabled {
+            return Err(RustFinderError::ConfigError(
+                "--fail-on-unresolved requer a resolução DNS habilitada (resolver.enabled = true)".to_string(),
+            ));
+        }
 
-        Self::new_with_args_and_config(args, config).await
+        Self::new_with_args_and_config(args, config, registry).await
     }
 
     async fn new_with_args_and_config(
         args: Args,
-        config: Config,
+        mut config: Config,
+        registry: SourceRegistry,
     ) -> Result<Self, RustFinderError> {
+        if config.resolver.wildcard_probes.is_empty() {
+            config.resolver.wildcard_probes = (0..WILDCARD_PROBE_COUNT).map(|_| utils::random_dns_label(16)).collect();
+        }
+
         let session = Session::new(&config)?;
         let sources = if let Some(source_names) = &args.sources {
             let mut sources = Vec::new();
             for name in source_names {
-                if let Some(source) = create_source(name, &config) {
+                if let Some(source) = create_source(name, &config).or_else(|| registry.create(name, &config)) {
                     sources.push(source);
                 } else {
                     warn!("[Engine] Fonte desconhecida: {}", name);
                 }
             }
             sources
+        } else if !config.sources.is_empty() {
+            let mut sources = Vec::new();
+            for name in &config.sources {
+                match create_source(name, &config).or_else(|| registry.create(name, &config)) {
+                    Some(source) => sources.push(source),
+                    None => {
+                        return Err(RustFinderError::ConfigError(format!(
+                            "Fonte desconhecida em config.sources: {}",
+                            name
+                        )))
+                    }
+                }
+            }
+            sources
         } else {
-            get_all_sources(&config)
+            let mut sources = get_all_sources(&config);
+            sources.extend(registry.create_all(&config));
+            sources
         };
 
+        let mut include_categories = args.category.iter()
+            .map(|c| c.trim().parse())
+            .collect::<Result<Vec<SourceCategory>, _>>()?;
+        if args.passive_dns_only {
+            include_categories.push(SourceCategory::PassiveDns);
+        }
+        let exclude_categories = args.exclude_category.iter()
+            .map(|c| c.trim().parse())
+            .collect::<Result<Vec<SourceCategory>, _>>()?;
+        let sources: Vec<Box<dyn Source>> = sources.into_iter()
+            .filter(|source| {
+                let category = source.info().category;
+                (include_categories.is_empty() || include_categories.contains(&category))
+                    && !exclude_categories.contains(&category)
+            })
+            // --only-sources-with-keys: descarta fontes com chave obrigatória ainda não configurada
+            .filter(|source| {
+                !args.only_sources_with_keys
+                    || !source.info().needs_key
+                    || config.api_keys.get(source.name()).is_some_and(|keys| !keys.is_empty())
+            })
+            .collect();
+
         if sources.is_empty() {
             return Err(RustFinderError::ConfigError(
                 "Nenhuma fonte válida configurada".to_string(),
@@ -71,28 +408,167 @@ impl RustFinderEngine {
         }
         info!("[Engine] {} fontes inicializadas", sources.len());
 
-        let resolver = if config.resolver.enabled {
-            Some(Arc::new(Resolver::new(config.resolver.clone())?))
+        let resolver: Option<Arc<dyn DnsResolver>> = if config.resolver.enabled {
+            let resolver = Resolver::new(config.resolver.clone())?;
+            // --dry-run não faz nenhuma consulta de rede
+            if config.resolver.probe_nameservers && !args.dry_run {
+                Self::check_nameservers(&resolver, &config.resolver).await?;
+            }
+            Some(Arc::new(resolver))
         } else {
             None
         };
 
-        let output_manager = OutputManager::new(config.output.clone());
+        let cache = match &config.cache_dir {
+            Some(dir) => {
+                info!("[Engine] Cache de respostas em {} (TTL {}s)", dir, config.cache_ttl.as_secs());
+                Some(Arc::new(ResponseCache::new(dir, config.cache_ttl)?))
+            }
+            None => None,
+        };
+
+        let include_cidrs = utils::parse_cidrs(&args.include_cidr)?;
+        let exclude_cidrs = utils::parse_cidrs(&args.exclude_cidr)?;
+        let first_seen_after = match &args.first_seen_after {
+            Some(value) => Some(chrono::NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").map_err(|_| {
+                RustFinderError::ConfigError(format!("Data inválida para --first-seen-after: {} (use AAAA-MM-DD)", value))
+            })?),
+            None => None,
+        };
+        let cdn_ranges = match &args.cdn_ranges {
+            Some(path) => {
+                let ranges = CdnRanges::load(path)?;
+                info!("[Engine] {} faixas de CDN carregadas de {:?}", ranges.len(), path);
+                ranges
+            }
+            None => CdnRanges::bundled(),
+        };
+
+        let mut output_manager = OutputManager::new(config.output.clone())
+            .with_silent(args.silent)
+            .with_streamed(args.stream)
+            .with_stderr_color(args.color.parse::<ColorChoice>()?.enabled(atty::Stream::Stderr));
+        if let Some(template) = &config.output.template {
+            output_manager = output_manager.with_template(FormatTemplate::parse(template)?);
+        }
+        if let Some(diff_path) = &args.diff {
+            let known = load_known_subdomains(diff_path)?;
+            info!("[Engine] {} subdomínios conhecidos carregados de {:?}", known.len(), diff_path);
+            output_manager = output_manager.with_known_subdomains(known);
+        }
 
         Ok(Self {
             config,
             session,
             sources,
             resolver,
+            cache,
+            include_cidrs,
+            exclude_cidrs,
+            cdn_ranges,
+            first_seen_after,
             output_manager,
             args,
+            run_id: uuid::Uuid::new_v4().to_string(),
+            batch_results: HashMap::new(),
+            unresolved_failures: Vec::new(),
+            interrupted: Arc::new(AtomicBool::new(false)),
+            interrupt_handler: std::sync::Once::new(),
         })
     }
 
+    async fn check_nameservers(resolver: &Resolver, config: &ResolverConfig) -> Result<(), RustFinderError> {
+        let unreachable = resolver.unreachable_nameservers().await;
+        if unreachable.is_empty() {
+            return Ok(());
+        }
+        if config.strict {
+            return Err(RustFinderError::ResolutionError(format!(
+                "Servidores DNS inacessíveis: {}",
+                unreachable.join(", ")
+            )));
+        }
+        for ns in &unreachable {
+            warn!("[Engine] Servidor DNS {} não respondeu à consulta de teste", ns);
+        }
+        if unreachable.len() == config.nameservers.len() {
+            warn!("[Engine] Nenhum servidor DNS respondeu; a resolução provavelmente virá vazia");
+        }
+        Ok(())
+    }
+
+    // Substitui o resolvedor DNS (ex.: por um mock nos testes ou outro backend)
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn with_resolver(mut self, resolver: Arc<dyn DnsResolver>) -> Self {
+        self.resolver = Some(resolver);
+        self
+    }
+
+    // Grava os relatórios com um formato próprio em vez do configurado em OutputFormat
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn with_report_writer(mut self, writer: Box<dyn ReportWriter>) -> Self {
+        self.output_manager = self.output_manager.with_writer(writer);
+        self
+    }
+
     pub fn args(&self) -> &Args {
         &self.args
     }
 
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    // Mostra o que seria consultado, sem nenhuma requisição de rede
+    pub fn dry_run(&self, domains: &[String]) {
+        println!("Domains ({}):", domains.len());
+        for domain in domains {
+            println!("  {}", domain);
+        }
+
+        println!("\nSources ({}):", self.sources.len());
+        println!("  {:<16} {:<14} RATE LIMIT", "SOURCE", "API KEY");
+        for source in &self.sources {
+            let name = source.name();
+            let key_status = if source.info().needs_key {
+                match self.config.api_keys.get(name).map(|keys| keys.len()).unwrap_or(0) {
+                    0 => "absent".to_string(),
+                    count => format!("present ({})", count),
+                }
+            } else {
+                "not required".to_string()
+            };
+            let rate_limit = match self.config.rate_limits.get(name).copied().flatten() {
+                Some(limit) => format!("{} req/s", limit),
+                None => "unlimited".to_string(),
+            };
+            println!("  {:<16} {:<14} {}", name, key_status, rate_limit);
+        }
+        if let Some(limit) = self.config.global_rate_limit {
+            println!("  global limit: {} req/s across all sources", limit);
+        }
+
+        let resolver = &self.config.resolver;
+        println!("\nResolver:");
+        if !resolver.enabled {
+            println!("  disabled");
+            return;
+        }
+        let nameservers = if resolver.use_system_resolver {
+            "system".to_string()
+        } else {
+            resolver.nameservers.join(", ")
+        };
+        println!("  protocol:     {:?}", resolver.protocol);
+        println!("  nameservers:  {}", nameservers);
+        println!("  rotate:       {}", resolver.rotate_nameservers);
+        println!("  records:      {}", resolver.records.join(", "));
+        println!("  concurrency:  {}", resolver.threads);
+        println!("  timeout:      {}s", resolver.timeout.as_secs());
+        println!("  force resolve: {}", resolver.force_resolve);
+        println!("  cache:        {}", resolver.cache);
+    }
+
     pub async fn run(&mut self, domains: Vec<String>) -> Result<EnumerationStats, RustFinderError> {
         if domains.is_empty() {
             return Err(RustFinderError::ConfigError(
@@ -100,19 +576,91 @@ impl RustFinderEngine {
             ));
         }
 
+        if let Some(path_template) = &self.config.output.path_template {
+            if domains.len() > 1 && !self.args.merge_output && !path_template.contains("{domain}") {
+                return Err(RustFinderError::ConfigError(
+                    "--output-template precisa de {domain} ao processar vários domínios".to_string(),
+                ));
+            }
+        }
+
         info!("[Engine] Iniciando enumeração para {} domínios", domains.len());
         let start_time = Instant::now();
+        let started_at = chrono::Utc::now().to_rfc3339();
         let mut total_found = 0;
         let mut unique_subdomains = 0;
         let mut resolved_count = 0;
+        let mut source_stats: HashMap<String, SourceStats> = HashMap::new();
+        let mut merged_domains: Vec<String> = Vec::new();
+        let mut merged_subdomains: HashMap<String, SubdomainResult> = HashMap::new();
+        let mut merged_errors: Vec<SourceError> = Vec::new();
+        // --low-memory: filtro de Bloom na frente de chaves e resultados gravados em disco, no lugar do mapa
+        let mut low_memory_dedup = if self.args.low_memory {
+            let dir = std::env::temp_dir().join(format!("rustfinder-dedup-{}", self.run_id));
+            Some(SpilledDedup::new(dir)?)
+        } else {
+            None
+        };
+
+        self.install_interrupt_handler();
+        let total_domains = domains.len();
+        self.output_manager.set_report_count(if self.args.merge_output { 1 } else { total_domains });
+        if total_domains > 1 {
+            self.prefetch_batches(&domains).await;
+        }
+
+        for (index, domain) in domains.into_iter().enumerate() {
+            if self.is_interrupted() {
+                warn!("[Engine] Interrompido: domínios restantes não serão enumerados");
+                break;
+            }
 
-        for domain in domains {
             match self.enumerate_domain(&domain).await {
                 Ok(report) => {
                     total_found += report.stats.total_found;
                     unique_subdomains += report.stats.unique_subdomains;
                     resolved_count += report.stats.resolved_count;
-                    self.output_manager.write_report(&report).await?;
+                    for (name, stats) in &report.stats.source_stats {
+                        let entry = source_stats.entry(name.clone()).or_default();
+                        entry.found += stats.found;
+                        entry.errors += stats.errors;
+                        entry.duration_ms += stats.duration_ms;
+                    }
+                    if self.args.merge_output {
+                        merged_domains.push(report.domain.clone());
+                        merged_errors.extend(report.errors.iter().map(|e| SourceError {
+                            source: e.source.clone(),
+                            message: format!("{}: {}", report.domain, e.message),
+                        }));
+                        if let Some(dedup) = low_memory_dedup.take() {
+                            let dedup_key = self.config.dedup_key;
+                            let subdomains = report.subdomains;
+                            let (dedup, outcome) = tokio::task::spawn_blocking(move || {
+                                let mut dedup = dedup;
+                                let outcome = subdomains
+                                    .iter()
+                                    .try_for_each(|s| dedup.insert(&dedup_key.key_for(s), s).map(|_| ()));
+                                (dedup, outcome)
+                            })
+                            .await
+                            .map_err(|e| RustFinderError::OutputError(format!("Falha na deduplicação em disco: {}", e)))?;
+                            outcome?;
+                            debug!(
+                                "[Engine] --low-memory: {} únicos, filtros com {} KiB",
+                                dedup.unique_count(),
+                                dedup.memory_bytes() / 1024
+                            );
+                            low_memory_dedup = Some(dedup);
+                        } else {
+                            for subdomain in report.subdomains {
+                                merged_subdomains
+                                    .entry(self.config.dedup_key.key_for(&subdomain))
+                                    .or_insert(subdomain);
+                            }
+                        }
+                    } else {
+                        self.output_manager.write_report(&report).await?;
+                    }
                     info!(
                         "[Engine] Enumeração para {} concluída: {} subdomínios únicos encontrados",
                         domain,
@@ -123,44 +671,263 @@ impl RustFinderEngine {
                     error!("[Engine] Falha ao enumerar {}: {}", domain, e);
                 }
             }
+
+            self.report_progress(index + 1, total_domains, &domain);
+        }
+        self.finish_progress();
+
+        if self.is_interrupted() {
+            eprintln!("[!] Execução interrompida: resultados parciais foram salvos");
+        }
+
+        let merged = if let Some(dedup) = low_memory_dedup {
+            let results = tokio::task::spawn_blocking(move || dedup.into_results())
+                .await
+                .map_err(|e| RustFinderError::OutputError(format!("Falha na deduplicação em disco: {}", e)))??;
+            Some(results)
+        } else if self.args.merge_output {
+            Some(merged_subdomains.into_values().collect::<Vec<_>>())
+        } else {
+            None
+        };
+        if let Some(merged) = &merged {
+            unique_subdomains = merged.len();
+            resolved_count = merged.iter().filter(|s| s.resolved).count();
         }
 
         let stats = EnumerationStats {
             total_found,
             unique_subdomains,
             resolved_count,
-            sources_used: self.sources.iter().map(|s| s.name().to_string()).collect(),
+            sources_used: Self::sources_with_data(&source_stats),
+            duration: start_time.elapsed(),
+            source_stats,
+        };
+
+        if let Some(mut subdomains) = merged.filter(|_| !merged_domains.is_empty()) {
+            utils::sort_results(&mut subdomains, self.config.output.sort_by);
+            let report = DomainReport::new(merged_domains.join(","), subdomains, stats.clone())
+                .with_run_id(&self.run_id)
+                .with_errors(merged_errors);
+            self.output_manager.write_report(&report).await?;
+        }
+        self.output_manager.write_summary_total(&stats)?;
+
+        if let Some(stats_path) = &self.args.stats_json {
+            let run_stats = RunStats {
+                run_id: self.run_id.clone(),
+                started_at,
+                finished_at: chrono::Utc::now().to_rfc3339(),
+                stats: stats.clone(),
+            };
+            write_stats_json(stats_path, &run_stats)?;
+        }
+
+        if let Some(command) = &self.args.on_complete {
+            self.run_on_complete(command, &stats).await;
+        }
+
+        if !self.unresolved_failures.is_empty() {
+            return Err(RustFinderError::UnresolvedSubdomains(self.unresolved_failures.join("; ")));
+        }
+
+        Ok(stats)
+    }
+
+    // Executa o comando de --on-complete; uma falha só é registrada, os resultados já foram gravados
+    async fn run_on_complete(&self, template: &str, stats: &EnumerationStats) {
+        let files = self.output_manager.written_files();
+        let command_line = utils::expand_command_template(template, &files, stats.unique_subdomains);
+        debug!("[Engine] Executando --on-complete: {}", command_line);
+
+        let mut command = if cfg!(windows) {
+            let mut command = tokio::process::Command::new("cmd");
+            command.arg("/C").arg(&command_line);
+            command
+        } else {
+            let mut command = tokio::process::Command::new("sh");
+            command.arg("-c").arg(&command_line);
+            command
+        };
+        if self.args.silent {
+            command.stdout(std::process::Stdio::null()).stderr(std::process::Stdio::null());
+        }
+
+        match command.status().await {
+            Ok(status) if status.success() => {}
+            Ok(status) => error!("[Engine] --on-complete terminou com {}: {}", status, command_line),
+            Err(e) => error!("[Engine] Falha ao executar --on-complete ({}): {}", command_line, e),
+        }
+    }
+
+    // Fontes com consulta em lote buscam todos os domínios de uma vez, em vez de um por vez no laço de run
+    async fn prefetch_batches(&mut self, domains: &[String]) {
+        let ascii_domains: Vec<String> = domains
+            .iter()
+            .filter_map(|domain| utils::to_ascii_domain(domain).ok())
+            .filter(|domain| Self::is_valid_domain(domain))
+            .collect();
+        let timeout_duration = self.config.source_timeout.saturating_mul(ascii_domains.len() as u32);
+
+        let mut batches = Vec::new();
+        for source in self.sources.iter().filter(|source| source.supports_batch()) {
+            // Domínios já em cache ficam fora do lote; o laço de run os lê do cache
+            let mut pending = Vec::new();
+            for domain in &ascii_domains {
+                let cached = match &self.cache {
+                    Some(cache) => cache.get(source.name(), domain).await.is_some(),
+                    None => false,
+                };
+                if !cached {
+                    pending.push(domain.clone());
+                }
+            }
+            if pending.is_empty() {
+                continue;
+            }
+            let session = &self.session;
+            batches.push(async move {
+                info!("[Engine] Consulta em lote em {} para {} domínios", source.name(), pending.len());
+                let outcome = timeout(timeout_duration, source.enumerate_batch(&pending, session)).await;
+                (source.name().to_string(), outcome)
+            });
+        }
+
+        let outcomes = futures::future::join_all(batches).await;
+        for (source_name, outcome) in outcomes {
+            match outcome {
+                Ok(Ok(results)) => {
+                    if let Some(cache) = &self.cache {
+                        for (domain, domain_results) in &results {
+                            if let Err(e) = cache.put(&source_name, domain, domain_results).await {
+                                warn!("[{}] Falha ao gravar cache: {}", source_name, e);
+                            }
+                        }
+                    }
+                    self.batch_results.insert(source_name, results);
+                }
+                Ok(Err(e)) => warn!("[{}] Consulta em lote falhou ({}); consultando um domínio por vez", source_name, e),
+                Err(_) => warn!("[{}] Timeout na consulta em lote; consultando um domínio por vez", source_name),
+            }
+        }
+    }
+
+    // Modo --reverse: consultas PTR sobre um IP ou bloco CIDR, filtrando pelos domínios informados (se houver)
+    pub async fn run_reverse(&mut self, target: &str, domains: &[String]) -> Result<EnumerationStats, RustFinderError> {
+        let resolver = self.resolver.clone().ok_or_else(|| {
+            RustFinderError::ConfigError("--reverse requer a resolução DNS habilitada (remova --no-resolve)".to_string())
+        })?;
+        let network: IpNetwork = target.trim().parse()
+            .map_err(|e| RustFinderError::ConfigError(format!("IP ou CIDR inválido {}: {}", target, e)))?;
+        let size = Self::reverse_host_count(&network);
+        if size > MAX_REVERSE_HOSTS {
+            return Err(RustFinderError::ConfigError(format!(
+                "Bloco {} tem {} endereços; o máximo para --reverse é {}",
+                network, size, MAX_REVERSE_HOSTS
+            )));
+        }
+
+        info!("[Engine] Consultando PTR para {} endereços em {}", size, network);
+        self.install_interrupt_handler();
+        let start_time = Instant::now();
+        let suffixes: Vec<String> = domains.iter().map(|d| format!(".{}", d.to_lowercase())).collect();
+        let matches_target = |host: &str| {
+            domains.is_empty()
+                || domains.iter().any(|d| host.eq_ignore_ascii_case(d))
+                || suffixes.iter().any(|suffix| host.ends_with(suffix.as_str()))
+        };
+
+        let mut lookups = futures::stream::iter(network.iter())
+            .map(|ip| {
+                let resolver = resolver.clone();
+                async move { (ip, resolver.resolve_ptr(ip).await) }
+            })
+            .buffer_unordered(self.config.resolver.threads);
+
+        let mut found: HashMap<String, SubdomainResult> = HashMap::new();
+        let mut total_found = 0;
+        while let Some((ip, hosts)) = lookups.next().await {
+            for host in hosts.into_iter().filter(|h| matches_target(h)) {
+                total_found += 1;
+                let entry = found.entry(host.clone()).or_insert_with(|| SubdomainResult {
+                    subdomain: host,
+                    source: "reverse-dns".to_string(),
+                    resolved: true,
+                    status: Some(ResolutionStatus::Resolved),
+                    ..Default::default()
+                });
+                let ip = ip.to_string();
+                if !entry.ip_addresses.contains(&ip) {
+                    entry.ip_addresses.push(ip);
+                }
+            }
+            if self.is_interrupted() {
+                warn!("[Engine] Interrompido: usando resultados parciais de {}", network);
+                break;
+            }
+        }
+
+        let mut subdomains: Vec<SubdomainResult> = found.into_values().collect();
+        utils::sort_results(&mut subdomains, self.config.output.sort_by);
+
+        let mut source_stats = HashMap::new();
+        source_stats.insert("reverse-dns".to_string(), SourceStats {
+            found: total_found,
+            errors: 0,
+            duration_ms: start_time.elapsed().as_millis() as u64,
+        });
+        let stats = EnumerationStats {
+            total_found,
+            unique_subdomains: subdomains.len(),
+            resolved_count: subdomains.len(),
+            sources_used: Self::sources_with_data(&source_stats),
             duration: start_time.elapsed(),
+            source_stats,
         };
 
+        let report = DomainReport::new(network.to_string(), subdomains, stats.clone())
+            .with_run_id(&self.run_id);
+        self.output_manager.write_report(&report).await?;
+        if self.is_interrupted() {
+            eprintln!("[!] Execução interrompida: resultados parciais foram salvos");
+        }
         Ok(stats)
     }
 
+    // Calculado pelo prefixo: net.size() estoura u32 num /0 IPv4
+    fn reverse_host_count(network: &IpNetwork) -> u128 {
+        let bits: u32 = if network.is_ipv4() { 32 } else { 128 };
+        let host_bits = bits - u32::from(network.prefix());
+        1u128.checked_shl(host_bits).unwrap_or(u128::MAX)
+    }
+
     pub async fn enumerate_domain(&mut self, domain: &str) -> Result<DomainReport, RustFinderError> {
-        if !Self::is_valid_domain(domain) {
+        let ascii_domain = utils::to_ascii_domain(domain)?;
+        if !Self::is_valid_domain(&ascii_domain) {
             return Err(RustFinderError::InvalidDomain(domain.to_string()));
         }
 
         info!("[Engine] Enumerando subdomínios para: {}", domain);
         let start_time = Instant::now();
-        let subdomains = self.enumerate_domain_internal(domain).await?;
+        let (mut subdomains, source_stats, source_errors) = self.enumerate_domain_internal(&ascii_domain).await?;
+        for subdomain in &mut subdomains {
+            subdomain.subdomain = utils::to_unicode_domain(&subdomain.subdomain);
+        }
         let unique_subdomains_count = subdomains.len();
         let resolved_count = subdomains.iter().filter(|s| s.resolved).count();
 
         let stats = EnumerationStats {
-            total_found: unique_subdomains_count,
+            total_found: source_stats.values().map(|s| s.found).sum(),
             unique_subdomains: unique_subdomains_count,
             resolved_count,
-            sources_used: self.sources.iter().map(|s| s.name().to_string()).collect(),
+            sources_used: Self::sources_with_data(&source_stats),
             duration: start_time.elapsed(),
+            source_stats,
         };
 
-        let report = DomainReport {
-            domain: domain.to_string(),
-            subdomains,
-            stats,
-            timestamp: chrono::Utc::now().to_rfc3339(),
-        };
+        let report = DomainReport::new(utils::to_unicode_domain(&ascii_domain), subdomains, stats)
+            .with_run_id(&self.run_id)
+            .with_errors(source_errors);
 
         Ok(report)
     }
@@ -168,40 +935,165 @@ impl RustFinderEngine {
     async fn enumerate_domain_internal(
         &mut self,
         domain: &str,
-    ) -> Result<Vec<SubdomainResult>, RustFinderError> {
+    ) -> Result<(Vec<SubdomainResult>, HashMap<String, SourceStats>, Vec<SourceError>), RustFinderError> {
         let all_results = Arc::new(Mutex::new(HashMap::new()));
+        let mut source_stats: HashMap<String, SourceStats> = HashMap::new();
+        let mut source_errors: Vec<SourceError> = Vec::new();
+        let total_sources = self.sources.len();
         let mut futures = FuturesUnordered::new();
-        let timeout_duration = TokioDuration::from_secs(self.config.timeout.as_secs());
+        let timeout_duration = TokioDuration::from_secs(self.config.source_timeout.as_secs());
+        let stream = self.args.stream;
+        let include_wildcards = self.config.include_wildcards;
+        let printer = Arc::new(StreamPrinter {
+            printed: std::sync::Mutex::new(HashSet::new()),
+            plain: self.config.output.plain,
+            drop_label: self.args.drop_label.clone(),
+            keep_label: self.args.keep_label.clone(),
+        });
 
-        for source in &self.sources {
+        // Com --stagger-ms, a ordem das fontes é embaralhada e cada uma começa com um atraso crescente
+        let stagger_ms = self.config.stagger_ms;
+        let mut order: Vec<usize> = (0..self.sources.len()).collect();
+        if stagger_ms > 0 {
+            order.shuffle(&mut rand::thread_rng());
+        }
+
+        for (position, index) in order.into_iter().enumerate() {
+            let source = &self.sources[index];
+            let start_delay = if stagger_ms > 0 {
+                TokioDuration::from_millis(
+                    stagger_ms.saturating_mul(position as u64) + rand::thread_rng().gen_range(0..stagger_ms),
+                )
+            } else {
+                TokioDuration::ZERO
+            };
             let source_name = source.name().to_string();
             let domain = domain.to_string();
             let session = self.session.clone();
             let source = source.clone_source();
             let all_results_clone = all_results.clone();
+            let cache = self.cache.clone();
+            let source_priority = self.config.source_priority.clone();
+            let dedup_key = self.config.dedup_key;
+            let printer = printer.clone();
+            let prefetched = self.batch_results.get_mut(&source_name).and_then(|batch| batch.remove(&domain));
 
             futures.push(async move {
                 debug!("[{}] Iniciando enumeração para {}", source_name, domain);
-                match timeout(timeout_duration, source.enumerate(&domain, &session)).await {
+                let mut stats = SourceStats::default();
+                let started = Instant::now();
+                let outcome = if let Some(results) = prefetched {
+                    debug!("[{}] Usando resultados da consulta em lote para {}", source_name, domain);
+                    Ok(Ok(results))
+                } else {
+                    let cached = match &cache {
+                        Some(cache) => cache.get(&source_name, &domain).await,
+                        None => None,
+                    };
+                    match cached {
+                        Some(cached) => {
+                            debug!("[{}] Usando resultados em cache para {}", source_name, domain);
+                            Ok(Ok(cached))
+                        }
+                        None => {
+                            if !start_delay.is_zero() {
+                                tokio::time::sleep(start_delay).await;
+                            }
+                            let outcome = if stream {
+                                timeout(
+                                    timeout_duration,
+                                    Self::enumerate_streaming(source.as_ref(), &domain, &session, &printer),
+                                )
+                                .await
+                            } else {
+                                timeout(
+                                    timeout_duration,
+                                    Self::enumerate_catching_panics(source.as_ref(), &domain, &session),
+                                )
+                                .await
+                            };
+                            if let (Some(cache), Ok(Ok(results))) = (&cache, &outcome) {
+                                if let Err(e) = cache.put(&source_name, &domain, results).await {
+                                    warn!("[{}] Falha ao gravar cache: {}", source_name, e);
+                                }
+                            }
+                            outcome
+                        }
+                    }
+                };
+                stats.duration_ms = started.elapsed().as_millis() as u64;
+                let mut failure = None;
+                match outcome {
                     Ok(Ok(subdomains)) => {
+                        stats.found = subdomains.len();
                         let mut results_guard = all_results_clone.lock().await;
-                        for subdomain in subdomains {
-                            let key = subdomain.subdomain.to_lowercase();
-                            results_guard.entry(key).or_insert(subdomain);
+                        for mut subdomain in subdomains {
+                            let Some(normalized) = utils::normalize_result(&subdomain.subdomain, &domain) else {
+                                debug!("[{}] Descartando entrada inválida: {}", source_name, subdomain.subdomain);
+                                continue;
+                            };
+                            if normalized.starts_with("*.") && !include_wildcards {
+                                continue;
+                            }
+                            subdomain.subdomain = normalized;
+                            match results_guard.entry(dedup_key.key_for(&subdomain)) {
+                                Entry::Vacant(entry) => {
+                                    if stream {
+                                        printer.print(&subdomain.subdomain, &subdomain.source);
+                                    }
+                                    entry.insert(subdomain);
+                                }
+                                Entry::Occupied(mut entry) => {
+                                    Self::merge_result(entry.get_mut(), subdomain, &source_priority);
+                                }
+                            }
                         }
                         debug!("[{}] Enumeração concluída", source_name);
                     }
                     Ok(Err(e)) => {
                         warn!("[{}] Erro: {}", source_name, e);
+                        stats.errors += 1;
+                        failure = Some(e.to_string());
                     }
                     Err(_) => {
                         warn!("[{}] Timeout", source_name);
+                        stats.errors += 1;
+                        failure = Some(
+                            RustFinderError::TimeoutError(format!("{}s excedidos", timeout_duration.as_secs()))
+                                .to_string(),
+                        );
                     }
                 }
+                (source_name, stats, failure)
             });
         }
 
-        futures.collect::<()>().await;
+        loop {
+            tokio::select! {
+                next = futures.next() => {
+                    match next {
+                        Some((source_name, stats, failure)) => {
+                            self.report_progress(
+                                source_stats.len() + 1,
+                                total_sources,
+                                &format!("{} ({})", domain, source_name),
+                            );
+                            if let Some(message) = failure {
+                                source_errors.push(SourceError { source: source_name.clone(), message });
+                            }
+                            source_stats.insert(source_name, stats);
+                        }
+                        None => break,
+                    }
+                }
+                _ = tokio::time::sleep(TokioDuration::from_millis(200)) => {}
+            }
+            if self.is_interrupted() {
+                warn!("[Engine] Interrompido: usando resultados parciais para {}", domain);
+                break;
+            }
+        }
+        drop(futures);
 
         let mut results: Vec<SubdomainResult> = Arc::try_unwrap(all_results)
             .unwrap()
@@ -213,16 +1105,557 @@ impl RustFinderEngine {
             warn!("[Engine] Nenhuma fonte retornou subdomínios.");
         }
 
-        if let Some(resolver) = &self.resolver {
+        if !self.args.drop_label.is_empty() || !self.args.keep_label.is_empty() {
+            let before = results.len();
+            results.retain(|s| utils::matches_label_filter(&s.subdomain, &self.args.drop_label, &self.args.keep_label));
+            debug!("[Engine] Filtro de rótulos removeu {} subdomínios", before - results.len());
+        }
+
+        // --first-seen-after: mantém só o que apareceu em certificado a partir da data
+        if let Some(cutoff) = self.first_seen_after {
+            let before = results.len();
+            let require_date = self.args.require_date;
+            results.retain(|s| match s.first_seen.as_deref().and_then(utils::first_seen_date) {
+                Some(date) => date >= cutoff,
+                None => !require_date,
+            });
+            debug!("[Engine] Filtro de data removeu {} subdomínios", before - results.len());
+        }
+
+        if let Some(resolver) = self.resolver.as_ref().filter(|_| !self.is_interrupted()) {
             info!("[Engine] Resolvendo {} subdomínios...", results.len());
             results = resolver.resolve_batch(results).await?;
+
+            // name-ip: antes da resolução a maioria das entradas não tem IP; agrupa de novo com os IPs resolvidos
+            if self.config.dedup_key == DedupKey::NameIp {
+                results = Self::dedup_results(results, self.config.dedup_key, &self.config.source_priority);
+            }
+
+            if self.config.resolver.filter_wildcards {
+                let wildcard_ips = self.detect_wildcard(resolver.as_ref(), domain).await;
+                if !wildcard_ips.is_empty() {
+                    let before = results.len();
+                    results.retain(|s| s.ip_addresses.is_empty() || !s.ip_addresses.iter().all(|ip| wildcard_ips.contains(ip)));
+                    info!("[Engine] Wildcard DNS em {}: {} subdomínios descartados", domain, before - results.len());
+                }
+            }
+
+            // --fail-on-unresolved: o relatório sai normalmente; run falha no final
+            if self.args.fail_on_unresolved {
+                let unresolved: Vec<&str> = results.iter()
+                    .filter(|s| !s.resolved)
+                    .map(|s| s.subdomain.as_str())
+                    .collect();
+                if !unresolved.is_empty() {
+                    let sample = unresolved.iter().take(UNRESOLVED_SAMPLE).copied().collect::<Vec<_>>().join(", ");
+                    let message = format!(
+                        "{} de {} subdomínios de {} não resolveram ({})",
+                        unresolved.len(),
+                        results.len(),
+                        domain,
+                        sample
+                    );
+                    warn!("[Engine] {}", message);
+                    self.unresolved_failures.push(message);
+                }
+            }
+
+            if self.args.only_resolved {
+                results.retain(|s| s.resolved);
+            }
+
+            if !self.include_cidrs.is_empty() || !self.exclude_cidrs.is_empty() {
+                results.retain(|s| {
+                    utils::matches_cidr_filter(&s.ip_addresses, &self.include_cidrs, &self.exclude_cidrs)
+                });
+            }
+        }
+
+        if !self.cdn_ranges.is_empty() {
+            for subdomain in &mut results {
+                subdomain.cdn = self.cdn_ranges.provider_for(&subdomain.ip_addresses).map(str::to_string);
+            }
+        }
+
+        if self.args.http_probe && !self.is_interrupted() {
+            info!("[Engine] Verificando HTTP em {} subdomínios...", results.len());
+            self.probe_http(&mut results).await;
+        }
+
+        utils::sort_results(&mut results, self.config.output.sort_by);
+        source_errors.sort_by(|a, b| a.source.cmp(&b.source));
+        Ok((results, source_stats, source_errors))
+    }
+
+    // Resolve os rótulos de teste sob o domínio; qualquer IP obtido indica wildcard DNS
+    async fn detect_wildcard(&self, resolver: &dyn DnsResolver, domain: &str) -> HashSet<String> {
+        let mut wildcard_ips = HashSet::new();
+        for label in &self.config.resolver.wildcard_probes {
+            let answer = resolver.resolve(&format!("{}.{}", label, domain)).await;
+            wildcard_ips.extend(answer.ips);
+        }
+        wildcard_ips
+    }
+
+    async fn probe_http(&self, results: &mut [SubdomainResult]) {
+        let check_all = self.resolver.is_none();
+        let targets: Vec<(usize, String)> = results
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| check_all || r.resolved)
+            .map(|(idx, r)| (idx, r.subdomain.clone()))
+            .collect();
+
+        let mut probes = futures::stream::iter(targets)
+            .map(|(idx, host)| async move { (idx, self.probe_host(&host).await) })
+            .buffer_unordered(HTTP_PROBE_CONCURRENCY);
+
+        while let Some((idx, probe)) = probes.next().await {
+            if let Some((status, redirect)) = probe {
+                results[idx].http_status = Some(status);
+                results[idx].http_redirect = redirect;
+            }
+        }
+    }
+
+    // Status da primeira resposta (https, depois http) e o Location quando for redirecionamento
+    async fn probe_host(&self, host: &str) -> Option<(u16, Option<String>)> {
+        for scheme in ["https", "http"] {
+            let url = format!("{}://{}", scheme, host);
+            match self.session.head(&url, "http-probe").await {
+                Ok(response) => {
+                    let status = response.status();
+                    let redirect = status
+                        .is_redirection()
+                        .then(|| response.headers().get(reqwest::header::LOCATION))
+                        .flatten()
+                        .and_then(|location| location.to_str().ok())
+                        .map(str::to_string);
+                    return Some((status.as_u16(), redirect));
+                }
+                Err(e) => debug!("[http-probe] {} falhou: {}", url, e),
+            }
+        }
+        None
+    }
+
+    // Um panic dentro de uma fonte vira um SourceError, sem derrubar a enumeração do domínio
+    async fn enumerate_catching_panics(
+        source: &dyn Source,
+        domain: &str,
+        session: &Session,
+    ) -> Result<Vec<SubdomainResult>, RustFinderError> {
+        match AssertUnwindSafe(source.enumerate(domain, session)).catch_unwind().await {
+            Ok(result) => result,
+            Err(panic) => Err(Self::panic_error(source.name(), panic)),
+        }
+    }
+
+    // Variante de --stream: consome enumerate_stream e exibe cada nome novo assim que chega
+    async fn enumerate_streaming(
+        source: &dyn Source,
+        domain: &str,
+        session: &Session,
+        printer: &StreamPrinter,
+    ) -> Result<Vec<SubdomainResult>, RustFinderError> {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(STREAM_BUFFER);
+        let producer = async move {
+            match AssertUnwindSafe(source.enumerate_stream(domain, session, tx)).catch_unwind().await {
+                Ok(result) => result,
+                Err(panic) => Err(Self::panic_error(source.name(), panic)),
+            }
+        };
+        let consumer = async {
+            let mut results = Vec::new();
+            while let Some(result) = rx.recv().await {
+                if let Some(name) = utils::normalize_result(&result.subdomain, domain) {
+                    if !name.starts_with("*.") {
+                        printer.print(&name, &result.source);
+                    }
+                }
+                results.push(result);
+            }
+            results
+        };
+        let (outcome, results) = tokio::join!(producer, consumer);
+        outcome.map(|()| results)
+    }
+
+    fn panic_error(source_name: &str, panic: Box<dyn std::any::Any + Send>) -> RustFinderError {
+        let message = panic
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "motivo desconhecido".to_string());
+        RustFinderError::SourceError {
+            source_name: source_name.to_string(),
+            message: format!("panic: {}", message),
+        }
+    }
+
+    fn dedup_results(results: Vec<SubdomainResult>, dedup_key: DedupKey, source_priority: &[String]) -> Vec<SubdomainResult> {
+        let mut unique: HashMap<String, SubdomainResult> = HashMap::new();
+        for result in results {
+            match unique.entry(dedup_key.key_for(&result)) {
+                Entry::Vacant(entry) => {
+                    entry.insert(result);
+                }
+                Entry::Occupied(mut entry) => Self::merge_result(entry.get_mut(), result, source_priority),
+            }
+        }
+        unique.into_values().collect()
+    }
+
+    // Mesmo nome vindo de outra fonte: junta IPs, fica com a fonte de maior prioridade e a data mais antiga
+    fn merge_result(existing: &mut SubdomainResult, subdomain: SubdomainResult, source_priority: &[String]) {
+        for ip in subdomain.ip_addresses {
+            if !existing.ip_addresses.contains(&ip) {
+                existing.ip_addresses.push(ip);
+            }
+        }
+        existing.resolved |= subdomain.resolved;
+        existing.derived &= subdomain.derived;
+        if let Some(first_seen) = subdomain.first_seen {
+            let earlier = match existing.first_seen.as_deref().and_then(utils::first_seen_date) {
+                Some(current) => utils::first_seen_date(&first_seen).is_some_and(|date| date < current),
+                None => true,
+            };
+            if earlier {
+                existing.first_seen = Some(first_seen);
+            }
         }
+        if Self::source_rank(source_priority, &subdomain.source)
+            < Self::source_rank(source_priority, &existing.source)
+        {
+            existing.source = subdomain.source;
+        }
+    }
+
+    // Menor é melhor: posição em source_priority, com desempate pelo nome para que a
+    // atribuição não dependa da ordem em que as fontes terminam
+    fn source_rank<'a>(priority: &[String], source: &'a str) -> (usize, &'a str) {
+        let position = priority
+            .iter()
+            .position(|p| p.eq_ignore_ascii_case(source))
+            .unwrap_or(usize::MAX);
+        (position, source)
+    }
+
+    fn sources_with_data(source_stats: &HashMap<String, SourceStats>) -> Vec<String> {
+        let mut sources: Vec<String> = source_stats.iter()
+            .filter(|(_, stats)| stats.found > 0)
+            .map(|(name, _)| name.clone())
+            .collect();
+        sources.sort();
+        sources
+    }
+
+    fn show_progress(&self) -> bool {
+        !self.args.silent && atty::is(atty::Stream::Stderr)
+    }
+
+    fn report_progress(&self, current: usize, total: usize, message: &str) {
+        if self.show_progress() {
+            let width = utils::terminal_width().saturating_sub(1);
+            eprint!("\r{:<width$}", utils::progress_message(current, total, message), width = width);
+            let _ = std::io::stderr().flush();
+        }
+    }
 
-        results.sort_by(|a, b| a.subdomain.cmp(&b.subdomain));
-        Ok(results)
+    fn finish_progress(&self) {
+        if self.show_progress() {
+            eprintln!();
+        }
+    }
+
+    fn install_interrupt_handler(&self) {
+        self.interrupt_handler.call_once(|| {
+            let interrupted = self.interrupted.clone();
+            tokio::spawn(async move {
+                if tokio::signal::ctrl_c().await.is_ok() {
+                    interrupted.store(true, Ordering::SeqCst);
+                    eprintln!("\n[!] Ctrl-C recebido: finalizando e salvando resultados parciais (pressione novamente para forçar a saída)");
+
+                    if tokio::signal::ctrl_c().await.is_ok() {
+                        eprintln!("[!] Saída forçada");
+                        std::process::exit(130);
+                    }
+                }
+            });
+        });
+    }
+
+    // Verdadeiro depois de um Ctrl-C: os resultados gravados são parciais
+    pub fn is_interrupted(&self) -> bool {
+        self.interrupted.load(Ordering::SeqCst)
     }
 
     fn is_valid_domain(domain: &str) -> bool {
         !domain.is_empty() && domain.len() <= 253 && domain.split('.').count() >= 2
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolver::MockResolver;
+    use crate::types::{SourceCategory, SourceInfo};
+    use async_trait::async_trait;
+
+    // Relatórios gravados pelo engine, para inspeção nos testes
+    #[derive(Clone, Default)]
+    struct CapturedReports(Arc<std::sync::Mutex<Vec<DomainReport>>>);
+
+    impl ReportWriter for CapturedReports {
+        fn write(&self, _writer: &mut dyn Write, report: &DomainReport) -> Result<(), RustFinderError> {
+            self.0.lock().unwrap().push(report.clone());
+            Ok(())
+        }
+    }
+
+    // Fonte que devolve sempre os mesmos nomes
+    #[derive(Clone)]
+    struct StaticSource(Vec<String>);
+
+    #[async_trait]
+    impl Source for StaticSource {
+        fn name(&self) -> &str {
+            "static"
+        }
+
+        fn info(&self) -> SourceInfo {
+            SourceInfo {
+                name: self.name().to_string(),
+                needs_key: false,
+                is_default: false,
+                category: SourceCategory::Search,
+            }
+        }
+
+        async fn enumerate(&self, _domain: &str, _session: &Session) -> Result<Vec<SubdomainResult>, RustFinderError> {
+            Ok(self.0.iter().map(|name| SubdomainResult {
+                subdomain: name.clone(),
+                source: self.name().to_string(),
+                ..Default::default()
+            }).collect())
+        }
+
+        fn clone_source(&self) -> Box<dyn Source> {
+            Box::new(self.clone())
+        }
+    }
+
+    // Engine sem rede: só a StaticSource e o MockResolver no lugar do resolvedor real
+    async fn mock_engine(
+        names: &[&str],
+        args: Args,
+        mut config: Config,
+        resolver: MockResolver,
+    ) -> (RustFinderEngine, CapturedReports) {
+        let names: Vec<String> = names.iter().map(|name| name.to_string()).collect();
+        let mut registry = SourceRegistry::new();
+        registry.register("static", move |_| Box::new(StaticSource(names.clone())));
+        config.resolver.enabled = false;
+        let args = Args {
+            sources: Some(vec!["static".to_string()]),
+            silent: true,
+            ..args
+        };
+
+        let reports = CapturedReports::default();
+        let engine = RustFinderEngine::new_with_args_and_config(args, config, registry)
+            .await
+            .unwrap()
+            .with_resolver(Arc::new(resolver))
+            .with_report_writer(Box::new(reports.clone()));
+        (engine, reports)
+    }
+
+    #[derive(Clone)]
+    struct PanickingSource;
+
+    #[async_trait]
+    impl Source for PanickingSource {
+        fn name(&self) -> &str {
+            "panicking"
+        }
+
+        fn info(&self) -> SourceInfo {
+            SourceInfo {
+                name: self.name().to_string(),
+                needs_key: false,
+                is_default: false,
+                category: SourceCategory::Search,
+            }
+        }
+
+        async fn enumerate(&self, _domain: &str, _session: &Session) -> Result<Vec<SubdomainResult>, RustFinderError> {
+            let data: Option<Vec<SubdomainResult>> = None;
+            Ok(data.expect("dados malformados"))
+        }
+
+        fn clone_source(&self) -> Box<dyn Source> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_panicking_source_becomes_error() {
+        let session = Session::new(&Config::default()).unwrap();
+        let result = RustFinderEngine::enumerate_catching_panics(&PanickingSource, "example.com", &session).await;
+
+        match result {
+            Err(RustFinderError::SourceError { source_name, message }) => {
+                assert_eq!(source_name, "panicking");
+                assert!(message.contains("dados malformados"));
+            }
+            other => panic!("esperado SourceError, obtido {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_name_ip_dedup_after_resolution() {
+        let result = |source: &str, ips: &[&str]| SubdomainResult {
+            subdomain: "www.example.com".to_string(),
+            source: source.to_string(),
+            ip_addresses: ips.iter().map(|ip| ip.to_string()).collect(),
+            ..Default::default()
+        };
+
+        // Fontes com IPs diferentes ficam separadas até a resolução
+        let collected = RustFinderEngine::dedup_results(
+            vec![result("crtsh", &[]), result("shodan", &["192.0.2.9"])],
+            DedupKey::NameIp,
+            &[],
+        );
+        assert_eq!(collected.len(), 2);
+
+        // Resolvidas para o mesmo endereço, viram uma entrada só
+        let resolved = collected.into_iter().map(|mut r| {
+            r.ip_addresses = vec!["192.0.2.1".to_string()];
+            r
+        }).collect();
+        let deduped = RustFinderEngine::dedup_results(resolved, DedupKey::NameIp, &[]);
+        assert_eq!(deduped.len(), 1);
+
+        let distinct = vec![result("crtsh", &["192.0.2.1"]), result("shodan", &["192.0.2.2"])];
+        assert_eq!(RustFinderEngine::dedup_results(distinct, DedupKey::NameIp, &[]).len(), 2);
+    }
+
+    #[test]
+    fn test_source_priority_is_order_independent() {
+        let result = |source: &str| SubdomainResult {
+            subdomain: "www.example.com".to_string(),
+            source: source.to_string(),
+            ..Default::default()
+        };
+        let priority = vec!["virustotal".to_string(), "crtsh".to_string()];
+
+        for order in [["hackertarget", "crtsh", "virustotal"], ["virustotal", "hackertarget", "crtsh"]] {
+            let merged = RustFinderEngine::dedup_results(order.iter().map(|s| result(s)).collect(), DedupKey::Name, &priority);
+            assert_eq!(merged[0].source, "virustotal");
+        }
+
+        // Fora da lista de prioridade, o desempate é pelo nome
+        for order in [["rapiddns", "hackertarget"], ["hackertarget", "rapiddns"]] {
+            let merged = RustFinderEngine::dedup_results(order.iter().map(|s| result(s)).collect(), DedupKey::Name, &priority);
+            assert_eq!(merged[0].source, "hackertarget");
+        }
+    }
+
+    #[test]
+    fn test_merge_keeps_earliest_first_seen() {
+        let result = |source: &str, first_seen: Option<&str>| SubdomainResult {
+            subdomain: "www.example.com".to_string(),
+            source: source.to_string(),
+            first_seen: first_seen.map(str::to_string),
+            ..Default::default()
+        };
+
+        // hackertarget (sem data) chega antes do crt.sh
+        let mut merged = result("hackertarget", None);
+        RustFinderEngine::merge_result(&mut merged, result("crtsh", Some("2023-05-01T00:00:00")), &[]);
+        assert_eq!(merged.first_seen.as_deref(), Some("2023-05-01T00:00:00"));
+
+        RustFinderEngine::merge_result(&mut merged, result("censys", Some("2021-02-03")), &[]);
+        RustFinderEngine::merge_result(&mut merged, result("certspotter", Some("2024-01-01")), &[]);
+        RustFinderEngine::merge_result(&mut merged, result("anubis", None), &[]);
+        assert_eq!(merged.first_seen.as_deref(), Some("2021-02-03"));
+    }
+
+    #[tokio::test]
+    async fn test_wildcard_answers_are_filtered() {
+        let resolver = MockResolver::default()
+            .with_answer("www.example.com", &["192.0.2.1"])
+            .with_answer("random.example.com", &["192.0.2.99"])
+            .with_answer("probe.example.com", &["192.0.2.99"]);
+        let mut config = Config::default();
+        config.resolver.filter_wildcards = true;
+        config.resolver.wildcard_probes = vec!["probe".to_string()];
+        let (mut engine, _) = mock_engine(
+            &["www.example.com", "random.example.com", "gone.example.com"],
+            Args::default(),
+            config,
+            resolver,
+        )
+        .await;
+
+        let report = engine.enumerate_domain("example.com").await.unwrap();
+        let names: Vec<&str> = report.subdomains.iter().map(|s| s.subdomain.as_str()).collect();
+        assert_eq!(names, vec!["gone.example.com", "www.example.com"]);
+        assert_eq!(report.stats.resolved_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_stream_rejects_post_resolution_filters() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("missing.toml");
+        let args = Args { stream: true, only_resolved: true, silent: true, ..Args::default() };
+
+        let result = RustFinderEngine::new(args, config_path.to_str().unwrap()).await;
+        assert!(matches!(result, Err(RustFinderError::ConfigError(message)) if message.contains("--only-resolved")));
+    }
+
+    #[test]
+    fn test_stream_printer_applies_label_filters() {
+        let printer = StreamPrinter {
+            printed: std::sync::Mutex::new(HashSet::new()),
+            plain: true,
+            drop_label: vec!["dev".to_string()],
+            keep_label: Vec::new(),
+        };
+        printer.print("dev.example.com", "crtsh");
+        printer.print("www.example.com", "crtsh");
+
+        let printed = printer.printed.lock().unwrap();
+        assert!(!printed.contains("dev.example.com"));
+        assert!(printed.contains("www.example.com"));
+    }
+
+    #[test]
+    fn test_reverse_host_count() {
+        let count = |cidr: &str| RustFinderEngine::reverse_host_count(&cidr.parse().unwrap());
+        assert_eq!(count("192.0.2.1"), 1);
+        assert_eq!(count("192.0.2.0/24"), 256);
+        assert_eq!(count("0.0.0.0/0"), 1 << 32);
+        assert_eq!(count("2001:db8::/64"), 1 << 64);
+        assert_eq!(count("::/0"), u128::MAX);
+    }
+
+    #[tokio::test]
+    async fn test_reverse_lookup_with_mock_resolver() {
+        let resolver = MockResolver::default()
+            .with_answer("www.example.com", &["192.0.2.1"])
+            .with_answer("mail.example.com", &["192.0.2.2"])
+            .with_answer("host.other.org", &["192.0.2.3"]);
+        let (mut engine, reports) = mock_engine(&[], Args::default(), Config::default(), resolver).await;
+
+        let stats = engine.run_reverse("192.0.2.0/30", &["example.com".to_string()]).await.unwrap();
+        assert_eq!(stats.unique_subdomains, 2);
+        let reports = reports.0.lock().unwrap();
+        let names: Vec<&str> = reports[0].subdomains.iter().map(|s| s.subdomain.as_str()).collect();
+        assert_eq!(names, vec!["mail.example.com", "www.example.com"]);
+
+        assert!(engine.run_reverse("0.0.0.0/0", &[]).await.is_err());
+    }
+}
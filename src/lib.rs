@@ -1,17 +1,24 @@
 // src/lib.rs
+pub mod bloom;
+pub mod cache;
+pub mod cdn;
 pub mod cli;
+pub mod color;
 pub mod config;
 pub mod engine;
 pub mod output;
 pub mod resolver;
 pub mod session;
 pub mod sources;
+pub mod tld;
 pub mod types;
 pub mod utils;
 
-pub use cli::Args;
+pub use cli::{Args, Cli, Command};
 pub use engine::RustFinderEngine;
-pub use types::{Config, RustFinderError, SubdomainResult, DomainReport};
+pub use output::ReportWriter;
+pub use sources::{Source, SourceRegistry};
+pub use types::{Config, RustFinderError, SubdomainResult, DomainReport, SourceError};
 
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const NAME: &str = env!("CARGO_PKG_NAME");
\ No newline at end of file
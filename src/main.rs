@@ -1,4 +1,4 @@
-use anyhow::Result;use clap::Parser;use log::{error, info};use std::process;use std::io::{self, BufRead};mod cli;mod config;mod engine;mod error;mod output;mod resolver;mod session;mod sources;mod types;mod updater;mod utils;use cli::Args;use engine::RustFinderEngine;use types::Config;const BANNER: &str = r#"
+use anyhow::Result;use clap::Parser;use log::{error, info, warn};use std::collections::HashSet;use std::process;use std::fs::OpenOptions;use std::io::{self, BufRead, Write};mod bloom;mod cache;mod cdn;mod cli;mod color;mod config;mod engine;mod error;mod output;mod resolver;mod session;mod sources;mod tld;mod types;mod updater;mod utils;use cli::{Args, Cli, Command};use engine::RustFinderEngine;use types::Config;const BANNER: &str = r#"
 
         ██████╗ ██╗   ██╗███████╗████████╗███████╗██╗███╗   ██╗██████╗ ███████╗██████╗ 
         ██╔══██╗██║   ██║██╔════╝╚══██╔══╝██╔════╝██║████╗  ██║██╔══██╗██╔════╝██╔══██╗
@@ -10,25 +10,65 @@ use anyhow::Result;use clap::Parser;use log::{error, info};use std::process;use
         Fast Passive Subdomain Enumeration
          Authors: Daniel Alisom
 "#;#[tokio::main]async fn main() -> Result<()> {
-    env_logger::Builder::from_default_env()
-        .filter_level(log::LevelFilter::Info)
-        .init();    let args = Args::parse();
+    match Cli::parse().into_command() {
+        Command::Enumerate(args) => run(args).await,
+        Command::Resolve(resolve) => run(resolve.into_args()).await,
+        Command::ListSources(log) => {
+            start(&log.into_args())?;
+            list_sources();
+            Ok(())
+        }
+        Command::CheckKeys(keys) => {
+            start(&keys.log.into_args())?;
+            let config_path = keys.config.config_path.unwrap_or_else(|| "config.toml".to_string());
+            let all_ok = check_keys(&config_path, keys.config.profile.as_deref()).await?;
+            if !all_ok {
+                process::exit(1);
+            }
+            Ok(())
+        }
+        Command::Update(log) => {
+            start(&log.into_args())?;
+            updater::check_and_update().await.map_err(|e| anyhow::anyhow!(e))
+        }
+    }
+}
+
+fn start(args: &Args) -> Result<()> {
+    init_logger(args)?;
+    let color = args.color.parse::<color::ColorChoice>()?.enabled(atty::Stream::Stderr);
     if !args.silent {
-        println!("{}", BANNER);
+        // Banner e diagnósticos vão para o stderr; o stdout fica só com os resultados
+        eprintln!("{}", color::Style::new(color).cyan(BANNER));
     }
-    if args.list_sources {
-        list_sources();
-        return Ok(());
-    }    if args.update {
-        return updater::check_and_update().await.map_err(|e| anyhow::anyhow!(e));
-    }    let domains = get_domains_from_args(&args); 
-    if domains.is_empty() && !args.use_stdin() {
+    Ok(())
+}
+
+// Enumeração e modo reverso (subcomando resolve) compartilham o mesmo motor
+async fn run(args: Args) -> Result<()> {
+    start(&args)?;
+    let domains = get_domains_from_args(&args).await?;
+    if domains.is_empty() && !args.use_stdin() && args.reverse.is_none() && !(args.dry_run && args.list_url.is_some()) {
         error!("No input provided. Use -d <domain>, -l <file>, or pipe domains to stdin");
         process::exit(1);
     }
     let config_path = args.config_path.clone().unwrap_or_else(|| "config.toml".to_string());
     let mut engine = RustFinderEngine::new(args.clone(), &config_path).await?;
 
+    if args.dry_run {
+        engine.dry_run(&domains);
+        return Ok(());
+    }
+
+    if let Some(target) = &args.reverse {
+        let stats = engine.run_reverse(target, &domains).await.map_err(|e| anyhow::anyhow!("Reverse lookup failed: {}", e))?;
+        if !args.silent {
+            info!("Reverse lookup completed: {} hostnames found in {:.2}s", stats.unique_subdomains, stats.duration.as_secs_f64());
+        }
+        exit_if_interrupted(&engine);
+        return Ok(());
+    }
+
     let stats = engine.run(domains).await.map_err(|e| anyhow::anyhow!("Enumeration failed: {}", e))?;
 
     if !engine.args().silent {
@@ -38,10 +78,71 @@ use anyhow::Result;use clap::Parser;use log::{error, info};use std::process;use
             stats.sources_used.len(),
             stats.duration.as_secs_f64()
         );
+        if engine.args().verbose {
+            info!("Run id: {}", engine.run_id());
+        }
     }
+    exit_if_interrupted(&engine);
 
     Ok(())
-}fn list_sources() {    println!("Available sources:\n");
+}
+
+// Ctrl-C com os parciais já gravados: sai com 130, como um processo encerrado por SIGINT
+fn exit_if_interrupted(engine: &RustFinderEngine) {
+    if engine.is_interrupted() {
+        let _ = io::stdout().flush();
+        process::exit(130);
+    }
+}struct TeeWriter {
+    file: std::fs::File,
+}
+
+impl Write for TeeWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        io::stderr().write_all(buf)?;
+        self.file.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()?;
+        self.file.flush()
+    }
+}
+
+fn init_logger(args: &Args) -> Result<()> {
+    let mut builder = env_logger::Builder::from_default_env();
+    builder.filter_level(log::LevelFilter::Info);
+
+    let write_style = match args.color.parse::<color::ColorChoice>()? {
+        color::ColorChoice::Always => env_logger::WriteStyle::Always,
+        color::ColorChoice::Never => env_logger::WriteStyle::Never,
+        color::ColorChoice::Auto => env_logger::WriteStyle::Auto,
+    };
+    builder.write_style(write_style);
+
+    if let Some(level) = &args.log_level {
+        builder.parse_filters(level);
+    }
+
+    if args.quiet_sources {
+        builder.filter_module("rustfinder::sources", log::LevelFilter::Warn);
+    }
+
+    if let Some(log_file) = &args.log_file {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log_file)
+            .map_err(|e| anyhow::anyhow!("Failed to open log file {:?}: {}", log_file, e))?;
+        builder.target(env_logger::Target::Pipe(Box::new(TeeWriter { file })));
+    }
+
+    builder.init();
+    Ok(())
+}
+
+fn list_sources() {    println!("Available sources:\n");
 
     let config = Config::default();
     let sources = sources::get_all_sources(&config);
@@ -52,15 +153,16 @@ use anyhow::Result;use clap::Parser;use log::{error, info};use std::process;use
     for source in sources {
         let info = source.info();
         let marker = if info.needs_key { " *" } else { "" };
+        let entry = format!("{:<16} [{}]{}", info.name, info.category, marker);
 
         if info.is_default {
             if info.needs_key {
-                api_sources.push(format!("{}{}", info.name, marker));
+                api_sources.push(entry);
             } else {
-                default_sources.push(format!("{}{}", info.name, marker));
+                default_sources.push(entry);
             }
         } else {
-            free_sources.push(format!("{}{}", info.name, marker));
+            free_sources.push(entry);
         }
     }
 
@@ -81,32 +183,260 @@ use anyhow::Result;use clap::Parser;use log::{error, info};use std::process;use
 
     println!("\n* = Requires API key");
     println!("\nTo configure API keys, edit: ~/.config/rustfinder/config.yaml");
-}fn get_domains_from_args(args: &Args) -> Vec<String> {    let mut domains = Vec::new();
+}async fn check_keys(config_path: &str, profile: Option<&str>) -> Result<bool> {
+    let config = config::load_config(config_path, profile)?;
+    let session = session::Session::new(&config)?;
+    let mut all_ok = true;
+
+    println!("{:<16} {:<14} STATUS", "SOURCE", "KEY");
+
+    for source in sources::get_all_sources(&config) {
+        if !source.info().needs_key {
+            continue;
+        }
+
+        let keys = config.api_keys.get(source.name()).cloned().unwrap_or_default();
+        if keys.is_empty() {
+            println!("{:<16} {:<14} not configured", source.name(), "-");
+            continue;
+        }
+
+        for key in keys {
+            session.check_rate_limit(source.name()).await?;
+            let status = match source.validate_key(&key, &session).await {
+                Ok(()) => "OK".to_string(),
+                Err(e) => {
+                    all_ok = false;
+                    format!("FAIL ({})", e)
+                }
+            };
+            println!("{:<16} {:<14} {}", source.name(), mask_key(&key), status);
+        }
+    }
+
+    Ok(all_ok)
+}
+
+fn mask_key(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    if chars.len() <= 8 {
+        return "*".repeat(chars.len());
+    }
+    let head: String = chars[..4].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}...{}", head, tail)
+}
+
+async fn get_domains_from_args(args: &Args) -> Result<Vec<String>> {    let mut domains = Vec::new();
 
     if !args.domain.is_empty() {
         domains.extend(args.domain.clone());
     }
 
     if let Some(file_path) = &args.domains_file {
-        match utils::read_lines(file_path) {
-            Ok(lines) => {
-                for domain in lines {
-                    domains.push(domain.trim().to_string());
+        match args.input_format.parse::<types::InputFormat>()? {
+            types::InputFormat::Lines => match utils::read_lines(file_path) {
+                Ok(lines) => {
+                    domains.extend(lines.iter().filter_map(|line| utils::domain_list_entry(line)).map(str::to_string));
+                },
+                Err(e) => {
+                    error!("Failed to read domains from file {:?}: {}", file_path, e);
                 }
             },
-            Err(e) => {
-                error!("Failed to read domains from file {:?}: {}", file_path, e);
+            format => {
+                let contents = std::fs::read_to_string(file_path)
+                    .map_err(|e| anyhow::anyhow!("Failed to read domains from file {:?}: {}", file_path, e))?;
+                let parsed = if format == types::InputFormat::Csv {
+                    parse_csv_domains(&contents)
+                } else {
+                    parse_json_domains(&contents)
+                };
+                domains.extend(parsed.map_err(|e| anyhow::anyhow!("Invalid input file {:?}: {}", file_path, e))?);
             }
         }
     }
+    if let Some(url) = &args.list_url {
+        if args.dry_run {
+            println!("Would fetch domains from {}", url);
+        } else {
+            let lines = fetch_domain_list(args, url)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to fetch domain list from {}: {}", url, e))?;
+            domains.extend(lines);
+        }
+    }
     if !atty::is(atty::Stream::Stdin) {
         let stdin = io::stdin();
         for line in stdin.lock().lines() {
-            if let Ok(domain) = line {
-                domains.push(domain.trim().to_string());
+            if let Ok(line) = line {
+                if let Some(domain) = utils::domain_list_entry(&line) {
+                    domains.push(domain.to_string());
+                }
+            }
+        }
+    }
+
+    let domains = normalize_domains(domains);
+    match &args.valid_tlds {
+        Some(path) => filter_unknown_tlds(domains, path.as_deref()),
+        None => Ok(domains),
+    }
+}
+
+// --valid-tlds: descarta entradas com TLD inexistente (ex.: example.con) antes de enumerar
+fn filter_unknown_tlds(domains: Vec<String>, path: Option<&std::path::Path>) -> Result<Vec<String>> {
+    let tlds = match path {
+        Some(path) => tld::TldList::load(path)?,
+        None => tld::TldList::bundled(),
+    };
+    Ok(domains
+        .into_iter()
+        .filter(|domain| {
+            let known = tlds.matches(domain);
+            if !known {
+                warn!("Ignoring domain with unknown TLD: {}", domain);
+            }
+            known
+        })
+        .collect())
+}
+
+async fn fetch_domain_list(args: &Args, url: &str) -> std::result::Result<Vec<String>, types::RustFinderError> {
+    let config_path = args.config_path.clone().unwrap_or_else(|| "config.toml".to_string());
+    let mut config = config::load_config(&config_path, args.profile.as_deref())?;
+    if let Some(proxy) = &args.proxy {
+        config.proxy = Some(proxy.clone());
+    }
+    if let Some(proxy_http) = &args.proxy_http {
+        config.proxy_http = Some(proxy_http.clone());
+    }
+    if let Some(proxy_https) = &args.proxy_https {
+        config.proxy_https = Some(proxy_https.clone());
+    }
+
+    let session = session::Session::new(&config)?;
+    let response = session.get(url, "list-url").await?;
+    let text = session.read_text(response).await?;
+
+    Ok(text
+        .lines()
+        .filter_map(utils::domain_list_entry)
+        .map(|line| line.to_string())
+        .collect())
+}
+
+fn parse_csv_domains(contents: &str) -> Result<Vec<String>> {
+    let mut lines = contents.lines().filter(|line| !line.trim().is_empty());
+    let header = lines.next().ok_or_else(|| anyhow::anyhow!("CSV file is empty"))?;
+    let column = split_csv_line(header)
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case("domain"))
+        .ok_or_else(|| anyhow::anyhow!("CSV header has no 'domain' column"))?;
+
+    let mut domains = Vec::new();
+    for (index, line) in lines.enumerate() {
+        let fields = split_csv_line(line);
+        let domain = fields
+            .get(column)
+            .ok_or_else(|| anyhow::anyhow!("CSV row {} has no 'domain' field", index + 2))?;
+        domains.push(domain.clone());
+    }
+    Ok(domains)
+}
+
+// Separa uma linha CSV respeitando campos entre aspas
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field).trim().to_string()),
+            c => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+fn parse_json_domains(contents: &str) -> Result<Vec<String>> {
+    let value: serde_json::Value = serde_json::from_str(contents)
+        .map_err(|e| anyhow::anyhow!("malformed JSON: {}", e))?;
+    let entries = value
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("expected a JSON array of domains"))?;
+
+    entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            entry
+                .as_str()
+                .or_else(|| entry.get("domain").and_then(|d| d.as_str()))
+                .map(|domain| domain.to_string())
+                .ok_or_else(|| {
+                    anyhow::anyhow!("entry {} is neither a string nor an object with a 'domain' key", index)
+                })
+        })
+        .collect()
+}
+
+fn normalize_domains(inputs: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut domains = Vec::new();
+
+    for input in inputs {
+        if input.trim().is_empty() {
+            continue;
+        }
+
+        match utils::normalize_input_domain(&input) {
+            Some(domain) => {
+                if seen.insert(domain.clone()) {
+                    domains.push(domain);
+                }
             }
+            None => warn!("Ignoring invalid domain input: {}", input.trim()),
         }
     }
 
     domains
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_csv_line_respects_quotes() {
+        assert_eq!(split_csv_line(r#"a, "b,c" ,"say ""hi""""#), vec!["a", "b,c", r#"say "hi""#]);
+        assert_eq!(split_csv_line(""), vec![""]);
+    }
+
+    #[test]
+    fn test_parse_csv_domains() {
+        let csv = "owner,Domain\n\"Acme, Inc\",example.com\n\nbob,example.org\n";
+        assert_eq!(parse_csv_domains(csv).unwrap(), vec!["example.com", "example.org"]);
+
+        assert!(parse_csv_domains("owner,host\nacme,example.com\n").is_err());
+        assert!(parse_csv_domains("owner,domain\nacme\n").is_err());
+        assert!(parse_csv_domains("").is_err());
+    }
+
+    #[test]
+    fn test_parse_json_domains() {
+        let json = r#"["example.com", {"domain": "example.org", "owner": "acme"}]"#;
+        assert_eq!(parse_json_domains(json).unwrap(), vec!["example.com", "example.org"]);
+
+        assert!(parse_json_domains(r#"{"domain": "example.com"}"#).is_err());
+        assert!(parse_json_domains(r#"["example.com", 42]"#).is_err());
+        assert!(parse_json_domains("[\"example.com\"").is_err());
+    }
+}
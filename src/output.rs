@@ -1,150 +1,910 @@
 // src/output.rs
-use crate::types::{OutputFormat, OutputConfig, RustFinderError, SubdomainResult, DomainReport};
+use crate::color::Style;
+use crate::types::{EnumerationStats, OutputFormat, OutputConfig, ResolutionStatus, RunStats, RustFinderError, SubdomainResult, DomainReport};
+use log::info;
+use std::collections::HashSet;
 use std::io::Write;
-use std::fs::File;
+use std::fs::{File, OpenOptions};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::net::IpAddr;
 use std::path::Path;
+use std::str::FromStr;
+
+const PATH_TEMPLATE_FIELDS: &[&str] = &["domain", "date", "format"];
+
+const TEMPLATE_FIELDS: &[&str] = &[
+    "subdomain", "source", "resolved", "status", "ips", "first_seen", "is_new", "reference", "http_status", "cdn",
+    "http_redirect", "ttl", "resolve_ms",
+    "a", "aaaa", "cname", "mx", "ns", "txt",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum TemplatePart {
+    Literal(String),
+    Field(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct FormatTemplate {
+    parts: Vec<TemplatePart>,
+}
+
+impl FormatTemplate {
+    pub fn parse(template: &str) -> Result<Self, RustFinderError> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => {
+                                return Err(RustFinderError::ConfigError(format!(
+                                    "Unclosed placeholder in format template: {}",
+                                    template
+                                )))
+                            }
+                        }
+                    }
+                    let name = name.trim().to_lowercase();
+                    if !TEMPLATE_FIELDS.contains(&name.as_str()) {
+                        return Err(RustFinderError::ConfigError(format!(
+                            "Unknown placeholder {{{}}} in format template (available: {})",
+                            name,
+                            TEMPLATE_FIELDS.join(", ")
+                        )));
+                    }
+                    if !literal.is_empty() {
+                        parts.push(TemplatePart::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(TemplatePart::Field(name));
+                }
+                '}' => {
+                    return Err(RustFinderError::ConfigError(format!(
+                        "Unmatched '}}' in format template: {}",
+                        template
+                    )))
+                }
+                c => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            parts.push(TemplatePart::Literal(literal));
+        }
+
+        Ok(Self { parts })
+    }
+
+    pub fn render(&self, subdomain: &SubdomainResult) -> String {
+        let mut line = String::new();
+        for part in &self.parts {
+            match part {
+                TemplatePart::Literal(text) => line.push_str(text),
+                TemplatePart::Field(name) => line.push_str(&Self::field_value(subdomain, name)),
+            }
+        }
+        line
+    }
+
+    fn field_value(subdomain: &SubdomainResult, name: &str) -> String {
+        match name {
+            "subdomain" => subdomain.subdomain.clone(),
+            "source" => subdomain.source.clone(),
+            "resolved" => subdomain.resolved.to_string(),
+            "status" => subdomain.status.map(|s| s.to_string()).unwrap_or_default(),
+            "ips" => subdomain.ip_addresses.join(";"),
+            "first_seen" => subdomain.first_seen.clone().unwrap_or_default(),
+            "is_new" => subdomain.is_new.map(|v| v.to_string()).unwrap_or_default(),
+            "reference" => subdomain.reference.clone().unwrap_or_default(),
+            "http_status" => subdomain.http_status.map(|s| s.to_string()).unwrap_or_default(),
+            "cdn" => subdomain.cdn.clone().unwrap_or_default(),
+            "http_redirect" => subdomain.http_redirect.clone().unwrap_or_default(),
+            "ttl" => subdomain.ttl.map(|t| t.to_string()).unwrap_or_default(),
+            "resolve_ms" => subdomain.resolve_ms.map(|ms| ms.to_string()).unwrap_or_default(),
+            record_type => subdomain
+                .records
+                .get(&record_type.to_uppercase())
+                .map(|values| values.join(";"))
+                .unwrap_or_default(),
+        }
+    }
+}
 
 pub struct OutputManager {
     config: OutputConfig,
+    known_subdomains: Option<HashSet<String>>,
+    writer: Option<Box<dyn ReportWriter>>,
+    file_started: AtomicBool,
+    written_files: Mutex<Vec<String>>,
+    // Nomes novos e resolvidos gravados com --only-new, para a linha total do --summary
+    only_new_totals: Mutex<(usize, usize)>,
+    report_count: usize,
+    silent: bool,
+    // Com --stream os nomes já foram para o stdout; o relatório final não os repete lá
+    streamed: bool,
+    // config.color vale para o stdout; o cabeçalho vai para o stderr, que pode não ser terminal
+    stderr_color: bool,
 }
 
 impl OutputManager {
     pub fn new(config: OutputConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            known_subdomains: None,
+            writer: None,
+            file_started: AtomicBool::new(false),
+            written_files: Mutex::new(Vec::new()),
+            only_new_totals: Mutex::new((0, 0)),
+            report_count: 1,
+            silent: false,
+            streamed: false,
+            stderr_color: false,
+        }
+    }
+
+    pub fn with_silent(mut self, silent: bool) -> Self {
+        self.silent = silent;
+        self
+    }
+
+    pub fn with_streamed(mut self, streamed: bool) -> Self {
+        self.streamed = streamed;
+        self
+    }
+
+    pub fn with_stderr_color(mut self, color: bool) -> Self {
+        self.stderr_color = color;
+        self
+    }
+
+    pub fn with_known_subdomains(mut self, known: HashSet<String>) -> Self {
+        self.known_subdomains = Some(known);
+        self
+    }
+
+    pub fn with_template(mut self, template: FormatTemplate) -> Self {
+        self.writer = Some(Box::new(template));
+        self
+    }
+
+    // Substitui o formato configurado por um ReportWriter próprio
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub fn with_writer(mut self, writer: Box<dyn ReportWriter>) -> Self {
+        self.writer = Some(writer);
+        self
+    }
+
+    // Quantos relatórios a execução vai gravar; com mais de um no mesmo -o, o JSON sai em JSON Lines
+    pub fn set_report_count(&mut self, count: usize) {
+        self.report_count = count;
     }
 
     pub async fn write_report(&self, report: &DomainReport) -> Result<(), RustFinderError> {
-        if let Some(file_path) = &self.config.file {
-            self.write_to_file(file_path, report).await
+        let report = self.apply_diff(report);
+        if let Some(path_template) = &self.config.path_template {
+            let file_path = expand_path_template(path_template, &report.domain, self.extension());
+            self.write_to_file(&file_path, &report, self.config.append).await
+        } else if let Some(dir) = &self.config.dir {
+            let file_path = Path::new(dir).join(self.report_file_name(&report.domain));
+            self.write_to_file(&file_path.to_string_lossy(), &report, self.config.append).await
+        } else if let Some(file_path) = &self.config.file {
+            // Vários domínios no mesmo arquivo: trunca na primeira escrita e anexa nas seguintes
+            let append = self.file_started.swap(true, Ordering::SeqCst) || self.config.append;
+            self.write_to_file(file_path, &report, append).await
         } else {
-            self.write_to_stdout(report).await
+            self.write_to_stdout(&report).await
         }
     }
 
-    async fn write_to_file(&self, file_path: &str, report: &DomainReport) -> Result<(), RustFinderError> {
+    fn apply_diff(&self, report: &DomainReport) -> DomainReport {
+        let mut report = report.clone();
+        if let Some(known) = &self.known_subdomains {
+            for subdomain in &mut report.subdomains {
+                subdomain.is_new = Some(!known.contains(&subdomain.subdomain.to_lowercase()));
+            }
+            if self.config.only_new {
+                report.subdomains.retain(|s| s.is_new == Some(true));
+                // As estatísticas passam a descrever só os nomes que ficaram no relatório
+                let subdomains = &report.subdomains;
+                let stats = &mut report.stats;
+                stats.total_found = subdomains.len();
+                stats.unique_subdomains = subdomains.len();
+                stats.resolved_count = subdomains.iter().filter(|s| s.resolved).count();
+                for (name, source) in &mut stats.source_stats {
+                    source.found = subdomains.iter().filter(|s| &s.source == name).count();
+                }
+                stats.sources_used.retain(|name| subdomains.iter().any(|s| &s.source == name));
+                if let Ok(mut totals) = self.only_new_totals.lock() {
+                    totals.0 += stats.unique_subdomains;
+                    totals.1 += stats.resolved_count;
+                }
+            }
+        }
+        report
+    }
+
+    fn json_lines(&self) -> bool {
+        let shared_file = self.config.file.is_some() && self.config.path_template.is_none() && self.config.dir.is_none();
+        self.config.append || (shared_file && self.report_count > 1)
+    }
+
+    fn extension(&self) -> &'static str {
+        match self.config.format {
+            OutputFormat::Text => "txt",
+            OutputFormat::Json => "json",
+            OutputFormat::Csv => "csv",
+        }
+    }
+
+    fn report_file_name(&self, domain: &str) -> String {
+        format!("{}.{}", safe_file_name(domain), self.extension())
+    }
+
+    async fn write_to_file(&self, file_path: &str, report: &DomainReport, append: bool) -> Result<(), RustFinderError> {
 
         if let Some(parent) = Path::new(file_path).parent() {
             std::fs::create_dir_all(parent)
                 .map_err(|e| RustFinderError::OutputError(format!("Failed to create directory: {}", e)))?;
         }
 
-        let mut file = File::create(file_path)
-            .map_err(|e| RustFinderError::OutputError(format!("Failed to create file: {}", e)))?;
-            
-        self.write_output(&mut file, report)?;
-        
-        println!("Results written to: {}", file_path);
+        // Ao anexar a um arquivo vazio ou novo, o cabeçalho CSV ainda é necessário
+        let with_header = !append || std::fs::metadata(file_path).map(|m| m.len() == 0).unwrap_or(true);
+
+        let mut file = if append {
+            OpenOptions::new().append(true).create(true).open(file_path)
+        } else {
+            File::create(file_path)
+        }
+        .map_err(|e| RustFinderError::OutputError(format!("Failed to create file: {}", e)))?;
+
+        self.write_output(&mut file, report, with_header, Style::plain())?;
+
+        if let Ok(mut written) = self.written_files.lock() {
+            if !written.iter().any(|path| path == file_path) {
+                written.push(file_path.to_string());
+            }
+        }
+
+        // Vai para o log (stderr), mantendo o stdout só com dados
+        if !self.silent {
+            info!("Results written to: {}", file_path);
+        }
         Ok(())
     }
 
+    // No stdout só vão os dados; o cabeçalho do modo texto (totais, fontes) vai para o stderr
     async fn write_to_stdout(&self, report: &DomainReport) -> Result<(), RustFinderError> {
+        let style = Style::new(self.config.color);
         let stdout = std::io::stdout();
         let mut handle = stdout.lock();
-        self.write_output(&mut handle, report)?;
+        if self.writer.is_none() && self.config.format == OutputFormat::Text && !self.config.summary {
+            if !self.silent && !self.config.plain {
+                let header = TextWriter { config: &self.config, style: Style::new(self.stderr_color) };
+                header.write_header(&mut std::io::stderr().lock(), report)?;
+            }
+            if self.streamed {
+                return Ok(());
+            }
+            let text = TextWriter { config: &self.config, style };
+            return text.write_lines(&mut handle, report);
+        }
+        if self.streamed && !self.config.summary {
+            return Ok(());
+        }
+        self.write_output(&mut handle, report, true, style)?;
         Ok(())
     }
 
-    fn write_output<W: Write>(&self, writer: &mut W, report: &DomainReport) -> Result<(), RustFinderError> {
+    fn write_output(
+        &self,
+        writer: &mut dyn Write,
+        report: &DomainReport,
+        with_header: bool,
+        style: Style,
+    ) -> Result<(), RustFinderError> {
+        if let Some(custom) = &self.writer {
+            return custom.write(writer, report);
+        }
+
         match self.config.format {
-            OutputFormat::Text => self.write_text_output(writer, report),
-            OutputFormat::Json => self.write_json_output(writer, report),
-            OutputFormat::Csv => self.write_csv_output(writer, report),
+            OutputFormat::Text => TextWriter { config: &self.config, style }.write(writer, report),
+            OutputFormat::Json => JsonWriter { config: &self.config, lines: self.json_lines() }.write(writer, report),
+            OutputFormat::Csv => CsvWriter { config: &self.config, with_header }.write(writer, report),
+        }
+    }
+
+    // Arquivos gravados até agora, na ordem da primeira escrita
+    pub fn written_files(&self) -> Vec<String> {
+        self.written_files.lock().map(|written| written.clone()).unwrap_or_default()
+    }
+
+    // Linha final do modo --summary com os totais de todos os domínios, no mesmo destino das
+    // linhas por domínio (com um arquivo por domínio, não há um arquivo comum: vai para o stdout)
+    pub fn write_summary_total(&self, stats: &EnumerationStats) -> Result<(), RustFinderError> {
+        if !self.config.summary || self.config.format != OutputFormat::Text || self.writer.is_some() {
+            return Ok(());
+        }
+        let mut stats = stats.clone();
+        if self.config.only_new && self.known_subdomains.is_some() {
+            let (unique, resolved) = self.only_new_totals.lock().map(|totals| *totals).unwrap_or_default();
+            stats.total_found = unique;
+            stats.unique_subdomains = unique;
+            stats.resolved_count = resolved;
+        }
+        let line = summary_line("total", &stats);
+        match &self.config.file {
+            Some(file_path) if self.config.path_template.is_none() && self.config.dir.is_none() => {
+                let mut file = OpenOptions::new()
+                    .append(true)
+                    .create(true)
+                    .open(file_path)
+                    .map_err(|e| RustFinderError::OutputError(format!("Failed to open file: {}", e)))?;
+                writeln!(file, "{}", line)
+                    .map_err(|e| RustFinderError::OutputError(format!("Failed to write summary: {}", e)))
+            }
+            _ => {
+                println!("{}", line);
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn write_subdomains(&self, subdomains: &[SubdomainResult]) -> Result<(), RustFinderError> {
+        if self.config.verbose {
+            for subdomain in subdomains {
+                if self.config.include_ips && !subdomain.ip_addresses.is_empty() {
+                    println!(
+                        "[{}] {} - {}",
+                        subdomain.source,
+                        subdomain.subdomain,
+                        subdomain.ip_addresses.join(", ")
+                    );
+                } else {
+                    println!("[{}] {}", subdomain.source, subdomain.subdomain);
+                }
+            }
         }
+        Ok(())
     }
+}
+
+// Formato de saída plugável: além dos embutidos (texto, JSON, CSV e --format-template),
+// quem usa a crate como biblioteca registra o próprio com OutputManager::with_writer
+pub trait ReportWriter: Send + Sync {
+    fn write(&self, writer: &mut dyn Write, report: &DomainReport) -> Result<(), RustFinderError>;
+}
+
+struct TextWriter<'a> {
+    config: &'a OutputConfig,
+    style: Style,
+}
 
-    fn write_text_output<W: Write>(&self, writer: &mut W, report: &DomainReport) -> Result<(), RustFinderError> {
-        writeln!(writer, "\n[*] Domain: {}", report.domain)
+impl TextWriter<'_> {
+    fn write_header(&self, writer: &mut dyn Write, report: &DomainReport) -> Result<(), RustFinderError> {
+        let style = self.style;
+        let marker = style.cyan("[*]");
+        writeln!(writer, "\n{} Domain: {}", marker, style.bold(&report.domain))
             .map_err(|e| RustFinderError::OutputError(e.to_string()))?;
-        writeln!(writer, "[*] Found {} unique subdomains", report.stats.unique_subdomains)
+        writeln!(writer, "{} Found {} unique subdomains", marker, report.stats.unique_subdomains)
             .map_err(|e| RustFinderError::OutputError(e.to_string()))?;
-        writeln!(writer, "[*] Resolved: {}/{}", report.stats.resolved_count, report.stats.unique_subdomains)
+        writeln!(writer, "{} Resolved: {}/{}", marker, report.stats.resolved_count, report.stats.unique_subdomains)
             .map_err(|e| RustFinderError::OutputError(e.to_string()))?;
-        writeln!(writer, "[*] Duration: {:?}", report.stats.duration)
+        writeln!(writer, "{} Duration: {:?}", marker, report.stats.duration)
             .map_err(|e| RustFinderError::OutputError(e.to_string()))?;
-        writeln!(writer, "\n[*] Results:")
+        if self.config.verbose && !report.stats.source_stats.is_empty() {
+            // Fontes mais lentas primeiro, para facilitar o ajuste de timeouts
+            let mut sources: Vec<_> = report.stats.source_stats.iter().collect();
+            sources.sort_by(|a, b| b.1.duration_ms.cmp(&a.1.duration_ms).then_with(|| a.0.cmp(b.0)));
+            writeln!(writer, "{} Sources:", marker)
+                .map_err(|e| RustFinderError::OutputError(e.to_string()))?;
+            for (name, stats) in sources {
+                writeln!(
+                    writer,
+                    "    {:<16} {:>6} found {:>3} errors {:>8.2}s",
+                    name,
+                    stats.found,
+                    stats.errors,
+                    stats.duration_ms as f64 / 1000.0
+                )
+                .map_err(|e| RustFinderError::OutputError(e.to_string()))?;
+            }
+        }
+        writeln!(writer, "\n{} Results:", marker)
             .map_err(|e| RustFinderError::OutputError(e.to_string()))?;
+        Ok(())
+    }
 
+    fn write_lines(&self, writer: &mut dyn Write, report: &DomainReport) -> Result<(), RustFinderError> {
+        let style = self.style;
         for subdomain in &report.subdomains {
+            if self.config.plain {
+                writeln!(writer, "{}", subdomain.subdomain)
+                    .map_err(|e| RustFinderError::OutputError(e.to_string()))?;
+                continue;
+            }
+            let mut line = format!("{} {}", subdomain.subdomain, style.dim(&format!("[{}]", subdomain.source)));
             if self.config.include_ips && !subdomain.ip_addresses.is_empty() {
-                writeln!(
-                    writer,
-                    "{} [{}] - {}",
-                    subdomain.subdomain,
-                    subdomain.source,
-                    subdomain.ip_addresses.join(", ")
-                ).map_err(|e| RustFinderError::OutputError(e.to_string()))?;
-            } else {
-                writeln!(
-                    writer,
-                    "{} [{}]",
-                    subdomain.subdomain,
-                    subdomain.source
-                ).map_err(|e| RustFinderError::OutputError(e.to_string()))?;
+                line.push_str(&format!(" - {}", subdomain.ip_addresses.join(", ")));
+            }
+            if let Some(http_status) = subdomain.http_status {
+                match &subdomain.http_redirect {
+                    Some(location) => line.push_str(&format!(" [HTTP {} -> {}]", http_status, location)),
+                    None => line.push_str(&format!(" [HTTP {}]", http_status)),
+                }
+            }
+            if subdomain.is_new == Some(true) {
+                line.push_str(&format!(" {}", style.green("(new)")));
+            }
+            if self.config.verbose {
+                // Hosts atrás de CDN raramente expõem a origem; ficam marcados e esmaecidos
+                if let Some(cdn) = &subdomain.cdn {
+                    line.push_str(&format!(" {}", style.dim(&format!("[cdn: {}]", cdn))));
+                }
+                if let Some(first_seen) = &subdomain.first_seen {
+                    line.push_str(&format!(" (first seen: {})", first_seen));
+                }
+                if let Some(reference) = &subdomain.reference {
+                    line.push_str(&format!(" (ref: {})", reference));
+                }
+                if let Some(status) = subdomain.status.filter(|s| *s != ResolutionStatus::Resolved) {
+                    line.push_str(&format!(" {}", style.yellow(&format!("({})", status))));
+                }
             }
+            writeln!(writer, "{}", line)
+                .map_err(|e| RustFinderError::OutputError(e.to_string()))?;
         }
 
         Ok(())
     }
+}
+
+impl ReportWriter for TextWriter<'_> {
+    fn write(&self, writer: &mut dyn Write, report: &DomainReport) -> Result<(), RustFinderError> {
+        if self.config.summary {
+            writeln!(writer, "{}", summary_line(&report.domain, &report.stats))
+                .map_err(|e| RustFinderError::OutputError(e.to_string()))?;
+            return Ok(());
+        }
+
+        if !self.config.plain {
+            self.write_header(writer, report)?;
+        }
+        self.write_lines(writer, report)
+    }
+}
+
+struct JsonWriter<'a> {
+    config: &'a OutputConfig,
+    lines: bool,
+}
 
-    fn write_json_output<W: Write>(&self, writer: &mut W, report: &DomainReport) -> Result<(), RustFinderError> {
-        let json = serde_json::to_string_pretty(report)
+impl ReportWriter for JsonWriter<'_> {
+    fn write(&self, writer: &mut dyn Write, report: &DomainReport) -> Result<(), RustFinderError> {
+        let mut value = serde_json::to_value(report)
             .map_err(|e| RustFinderError::OutputError(format!("Failed to serialize JSON: {}", e)))?;
+
+        if self.config.include_ip_version {
+            if let Some(entries) = value.get_mut("subdomains").and_then(|v| v.as_array_mut()) {
+                for (entry, subdomain) in entries.iter_mut().zip(&report.subdomains) {
+                    let (ipv4, ipv6) = split_ip_versions(&subdomain.ip_addresses);
+                    if let Some(object) = entry.as_object_mut() {
+                        object.insert("ipv4".to_string(), serde_json::json!(ipv4));
+                        object.insert("ipv6".to_string(), serde_json::json!(ipv6));
+                    }
+                }
+            }
+        }
+
+        // Vários objetos JSON formatados no mesmo arquivo não são JSON válido; nesse caso usa JSON Lines
+        let json = if self.lines {
+            serde_json::to_string(&value)
+        } else {
+            serde_json::to_string_pretty(&value)
+        }
+        .map_err(|e| RustFinderError::OutputError(format!("Failed to serialize JSON: {}", e)))?;
         
         writeln!(writer, "{}", json)
             .map_err(|e| RustFinderError::OutputError(e.to_string()))?;
         
         Ok(())
     }
+}
 
-    fn write_csv_output<W: Write>(&self, writer: &mut W, report: &DomainReport) -> Result<(), RustFinderError> {
+struct CsvWriter<'a> {
+    config: &'a OutputConfig,
+    with_header: bool,
+}
 
-        if self.config.include_ips {
-            writeln!(writer, "subdomain,source,resolved,ip_addresses")
-                .map_err(|e| RustFinderError::OutputError(e.to_string()))?;
-        } else {
-            writeln!(writer, "subdomain,source,resolved")
+impl ReportWriter for CsvWriter<'_> {
+    fn write(&self, writer: &mut dyn Write, report: &DomainReport) -> Result<(), RustFinderError> {
+
+        if self.with_header {
+            let mut header = String::from("subdomain,source,resolved");
+            if self.config.include_ips {
+                header.push_str(",ip_addresses");
+            }
+            if self.config.include_ip_version {
+                header.push_str(",ipv4,ipv6");
+            }
+            writeln!(writer, "{}", header)
                 .map_err(|e| RustFinderError::OutputError(e.to_string()))?;
         }
 
         for subdomain in &report.subdomains {
+            let mut line = format!("{},{},{}", subdomain.subdomain, subdomain.source, subdomain.resolved);
             if self.config.include_ips {
-                writeln!(
-                    writer,
-                    "{},{},{},\"{}\"",
-                    subdomain.subdomain,
-                    subdomain.source,
-                    subdomain.resolved,
-                    subdomain.ip_addresses.join(", ")
-                ).map_err(|e| RustFinderError::OutputError(e.to_string()))?;
-            } else {
-                writeln!(
-                    writer,
-                    "{},{},{}",
-                    subdomain.subdomain,
-                    subdomain.source,
-                    subdomain.resolved
-                ).map_err(|e| RustFinderError::OutputError(e.to_string()))?;
+                line.push_str(&format!(",\"{}\"", subdomain.ip_addresses.join(", ")));
             }
+            if self.config.include_ip_version {
+                let (ipv4, ipv6) = split_ip_versions(&subdomain.ip_addresses);
+                line.push_str(&format!(",\"{}\",\"{}\"", ipv4.join(", "), ipv6.join(", ")));
+            }
+            writeln!(writer, "{}", line)
+                .map_err(|e| RustFinderError::OutputError(e.to_string()))?;
         }
 
         Ok(())
     }
+}
 
-    pub async fn write_subdomains(&self, subdomains: &[SubdomainResult]) -> Result<(), RustFinderError> {
-        if self.config.verbose {
-            for subdomain in subdomains {
-                if self.config.include_ips && !subdomain.ip_addresses.is_empty() {
-                    println!(
-                        "[{}] {} - {}",
-                        subdomain.source,
-                        subdomain.subdomain,
-                        subdomain.ip_addresses.join(", ")
-                    );
-                } else {
-                    println!("[{}] {}", subdomain.source, subdomain.subdomain);
+impl ReportWriter for FormatTemplate {
+    fn write(&self, writer: &mut dyn Write, report: &DomainReport) -> Result<(), RustFinderError> {
+        for subdomain in &report.subdomains {
+            writeln!(writer, "{}", self.render(subdomain))
+                .map_err(|e| RustFinderError::OutputError(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+fn safe_file_name(domain: &str) -> String {
+    domain
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+        .collect()
+}
+
+pub fn validate_path_template(template: &str) -> Result<(), RustFinderError> {
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..].find('}').ok_or_else(|| {
+            RustFinderError::ConfigError(format!("Unclosed placeholder in output template: {}", template))
+        })?;
+        let name = &rest[start + 1..start + end];
+        if !PATH_TEMPLATE_FIELDS.contains(&name) {
+            return Err(RustFinderError::ConfigError(format!(
+                "Unknown placeholder {{{}}} in output template (available: {})",
+                name,
+                PATH_TEMPLATE_FIELDS.join(", ")
+            )));
+        }
+        rest = &rest[start + end + 1..];
+    }
+    Ok(())
+}
+
+fn expand_path_template(template: &str, domain: &str, format: &str) -> String {
+    template
+        .replace("{domain}", &safe_file_name(domain))
+        .replace("{date}", &chrono::Utc::now().format("%Y-%m-%d").to_string())
+        .replace("{format}", format)
+}
+
+fn summary_line(label: &str, stats: &EnumerationStats) -> String {
+    format!(
+        "{}: {} subdomains, {} resolved, {} sources, {:.1}s",
+        label,
+        stats.unique_subdomains,
+        stats.resolved_count,
+        stats.sources_used.len(),
+        stats.duration.as_secs_f64()
+    )
+}
+
+fn split_ip_versions(ip_addresses: &[String]) -> (Vec<String>, Vec<String>) {
+    let mut ipv4 = Vec::new();
+    let mut ipv6 = Vec::new();
+    for ip in ip_addresses {
+        match IpAddr::from_str(ip) {
+            Ok(IpAddr::V4(_)) => ipv4.push(ip.clone()),
+            Ok(IpAddr::V6(_)) => ipv6.push(ip.clone()),
+            Err(_) => {}
+        }
+    }
+    (ipv4, ipv6)
+}
+
+pub fn load_known_subdomains(path: &Path) -> Result<HashSet<String>, RustFinderError> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| RustFinderError::OutputError(format!("Failed to read previous report {:?}: {}", path, e)))?;
+
+    let mut known = HashSet::new();
+    for value in serde_json::Deserializer::from_str(&contents).into_iter::<serde_json::Value>() {
+        let value = value
+            .map_err(|e| RustFinderError::ParseError(format!("Invalid previous report {:?}: {}", path, e)))?;
+
+        let reports: Vec<DomainReport> = if value.is_array() {
+            serde_json::from_value(value)
+        } else {
+            serde_json::from_value::<DomainReport>(value).map(|report| vec![report])
+        }
+        .map_err(|e| RustFinderError::ParseError(format!("Invalid previous report {:?}: {}", path, e)))?;
+
+        for report in reports {
+            for subdomain in report.subdomains {
+                known.insert(subdomain.subdomain.to_lowercase());
+            }
+        }
+    }
+
+    Ok(known)
+}
+
+pub fn write_stats_json(path: &Path, stats: &RunStats) -> Result<(), RustFinderError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| RustFinderError::OutputError(format!("Failed to create directory: {}", e)))?;
+    }
+
+    let json = serde_json::to_string_pretty(stats)
+        .map_err(|e| RustFinderError::OutputError(format!("Failed to serialize stats: {}", e)))?;
+    std::fs::write(path, json)
+        .map_err(|e| RustFinderError::OutputError(format!("Failed to write stats file: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{SourceError, SourceStats};
+
+    // Relatório de example.com com estatísticas mínimas
+    fn report(subdomains: Vec<SubdomainResult>) -> DomainReport {
+        let stats = EnumerationStats {
+            total_found: subdomains.len(),
+            unique_subdomains: subdomains.len(),
+            resolved_count: 0,
+            sources_used: Vec::new(),
+            duration: std::time::Duration::from_secs(0),
+            source_stats: Default::default(),
+        };
+        DomainReport::new("example.com".to_string(), subdomains, stats)
+    }
+
+    #[test]
+    fn test_format_template() {
+        let template = FormatTemplate::parse("{subdomain},{ips},{source},{cname} {{x}}").unwrap();
+        let mut result = SubdomainResult {
+            subdomain: "www.example.com".to_string(),
+            source: "crtsh".to_string(),
+            ip_addresses: vec!["1.2.3.4".to_string(), "5.6.7.8".to_string()],
+            ..Default::default()
+        };
+        result.records.insert("CNAME".to_string(), vec!["cdn.example.net".to_string()]);
+
+        assert_eq!(template.render(&result), "www.example.com,1.2.3.4;5.6.7.8,crtsh,cdn.example.net {x}");
+
+        result.ttl = Some(300);
+        result.resolve_ms = Some(12);
+        let timing = FormatTemplate::parse("{subdomain} ttl={ttl} {resolve_ms}ms").unwrap();
+        assert_eq!(timing.render(&result), "www.example.com ttl=300 12ms");
+        assert!(FormatTemplate::parse("{subdomain} {bogus}").is_err());
+        assert!(FormatTemplate::parse("{subdomain").is_err());
+    }
+
+    #[test]
+    fn test_split_ip_versions() {
+        let ips = vec!["1.2.3.4".to_string(), "2001:db8::1".to_string(), "bogus".to_string()];
+        let (ipv4, ipv6) = split_ip_versions(&ips);
+        assert_eq!(ipv4, vec!["1.2.3.4"]);
+        assert_eq!(ipv6, vec!["2001:db8::1"]);
+    }
+
+    #[test]
+    fn test_path_template() {
+        assert!(validate_path_template("scans/{domain}_{date}.{format}").is_ok());
+        assert!(validate_path_template("{host}.json").is_err());
+        assert!(validate_path_template("{domain.json").is_err());
+
+        let path = expand_path_template("out/{domain}.{format}", "example.com", "json");
+        assert_eq!(path, "out/example.com.json");
+    }
+
+    #[test]
+    fn test_summary_line() {
+        let stats = EnumerationStats {
+            total_found: 150,
+            unique_subdomains: 142,
+            resolved_count: 98,
+            sources_used: vec!["a".into(), "b".into(), "c".into(), "d".into(), "e".into(), "f".into()],
+            duration: std::time::Duration::from_millis(4200),
+            source_stats: Default::default(),
+        };
+        assert_eq!(
+            summary_line("example.com", &stats),
+            "example.com: 142 subdomains, 98 resolved, 6 sources, 4.2s"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_single_file_keeps_every_domain() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.txt");
+        let config = OutputConfig {
+            file: Some(path.to_string_lossy().to_string()),
+            ..OutputConfig::default()
+        };
+        let manager = OutputManager::new(config);
+
+        for domain in ["example.com", "example.net"] {
+            let report = DomainReport {
+                domain: domain.to_string(),
+                ..report(vec![SubdomainResult {
+                    subdomain: format!("www.{}", domain),
+                    source: "crtsh".to_string(),
+                    ..Default::default()
+                }])
+            };
+            manager.write_report(&report).await.unwrap();
+        }
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.contains("www.example.com"));
+        assert!(contents.contains("www.example.net"));
+    }
+
+    #[test]
+    fn test_only_new_recounts_stats() {
+        let subdomain = |name: &str, source: &str| SubdomainResult {
+            subdomain: name.to_string(),
+            source: source.to_string(),
+            ..Default::default()
+        };
+        let mut report = report(vec![subdomain("www.example.com", "crtsh"), subdomain("new.example.com", "shodan")]);
+        report.stats.sources_used = vec!["crtsh".to_string(), "shodan".to_string()];
+        report.stats.source_stats.insert("crtsh".to_string(), SourceStats { found: 1, ..Default::default() });
+        report.stats.source_stats.insert("shodan".to_string(), SourceStats { found: 1, ..Default::default() });
+
+        let config = OutputConfig { only_new: true, ..OutputConfig::default() };
+        let manager = OutputManager::new(config)
+            .with_known_subdomains(["www.example.com".to_string()].into_iter().collect());
+        let filtered = manager.apply_diff(&report);
+
+        assert_eq!(filtered.subdomains.len(), 1);
+        assert_eq!(filtered.stats.unique_subdomains, 1);
+        assert_eq!(filtered.stats.sources_used, vec!["shodan"]);
+        assert_eq!(filtered.stats.source_stats["crtsh"].found, 0);
+        assert_eq!(filtered.stats.source_stats["shodan"].found, 1);
+    }
+
+    #[tokio::test]
+    async fn test_json_lines_for_several_domains_in_one_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.json");
+        let config = OutputConfig {
+            file: Some(path.to_string_lossy().to_string()),
+            format: OutputFormat::Json,
+            ..OutputConfig::default()
+        };
+        let mut manager = OutputManager::new(config);
+        manager.set_report_count(2);
+
+        for domain in ["example.com", "example.net"] {
+            let report = DomainReport { domain: domain.to_string(), ..report(Vec::new()) };
+            manager.write_report(&report).await.unwrap();
+        }
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let domains: Vec<String> = contents
+            .lines()
+            .map(|line| serde_json::from_str::<DomainReport>(line).unwrap().domain)
+            .collect();
+        assert_eq!(domains, vec!["example.com", "example.net"]);
+    }
+
+    #[tokio::test]
+    async fn test_summary_total_goes_to_output_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("summary.txt");
+        let config = OutputConfig {
+            file: Some(path.to_string_lossy().to_string()),
+            summary: true,
+            ..OutputConfig::default()
+        };
+        let manager = OutputManager::new(config);
+        let report = report(Vec::new());
+        manager.write_report(&report).await.unwrap();
+        manager.write_summary_total(&report.stats).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].starts_with("total: "));
+    }
+
+    #[tokio::test]
+    async fn test_custom_report_writer() {
+        struct MarkdownWriter;
+
+        impl ReportWriter for MarkdownWriter {
+            fn write(&self, writer: &mut dyn Write, report: &DomainReport) -> Result<(), RustFinderError> {
+                writeln!(writer, "# {}", report.domain).map_err(|e| RustFinderError::OutputError(e.to_string()))?;
+                for subdomain in &report.subdomains {
+                    writeln!(writer, "- {}", subdomain.subdomain)
+                        .map_err(|e| RustFinderError::OutputError(e.to_string()))?;
                 }
+                Ok(())
             }
         }
-        Ok(())
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.md");
+        let config = OutputConfig {
+            file: Some(path.to_string_lossy().to_string()),
+            ..OutputConfig::default()
+        };
+        let manager = OutputManager::new(config).with_writer(Box::new(MarkdownWriter));
+        let report = report(vec![SubdomainResult {
+            subdomain: "www.example.com".to_string(),
+            ..Default::default()
+        }]);
+        manager.write_report(&report).await.unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "# example.com\n- www.example.com\n");
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_json_report_includes_source_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.json");
+        let config = OutputConfig {
+            file: Some(path.to_string_lossy().to_string()),
+            format: OutputFormat::Json,
+            ..OutputConfig::default()
+        };
+        let manager = OutputManager::new(config);
+        let report = report(Vec::new()).with_errors(vec![SourceError {
+            source: "shodan".to_string(),
+            message: "API key error: invalid".to_string(),
+        }]);
+        manager.write_report(&report).await.unwrap();
+
+        let parsed: DomainReport = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(parsed.errors, report.errors);
+    }
+
+    #[tokio::test]
+    async fn test_plain_text_has_bare_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.txt");
+        let config = OutputConfig {
+            file: Some(path.to_string_lossy().to_string()),
+            plain: true,
+            ..OutputConfig::default()
+        };
+        let manager = OutputManager::new(config);
+        let report = report(vec![SubdomainResult {
+            subdomain: "www.example.com".to_string(),
+            source: "crtsh".to_string(),
+            ip_addresses: vec!["192.0.2.1".to_string()],
+            ..Default::default()
+        }]);
+        manager.write_report(&report).await.unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "www.example.com\n");
+    }
+}
@@ -1,97 +1,602 @@
 // src/resolver.rs
-use crate::types::{RustFinderError, SubdomainResult, ResolverConfig};
+use crate::types::{ResolutionStatus, RustFinderError, SubdomainResult, ResolverConfig, ResolverProtocol};
+use async_trait::async_trait;
+use std::collections::HashMap;
 use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use tokio::sync::Semaphore;
 use trust_dns_resolver::TokioAsyncResolver;
-use trust_dns_resolver::config::{ResolverConfig as DnsResolverConfig, ResolverOpts};
+use trust_dns_resolver::error::{ResolveError, ResolveErrorKind};
+use trust_dns_resolver::proto::op::ResponseCode;
+use trust_dns_resolver::proto::rr::RecordType;
+use trust_dns_resolver::config::{NameServerConfig, Protocol, ResolverConfig as DnsResolverConfig, ResolverOpts};
 use futures::stream::{FuturesUnordered, StreamExt};
 
+pub const DEFAULT_DOH_NAMESERVERS: &[&str] = &["1.1.1.1:443", "1.0.0.1:443"];
+pub const DEFAULT_DOH_TLS_NAME: &str = "cloudflare-dns.com";
+
+// Nome resolvido na verificação dos servidores DNS na inicialização
+const NAMESERVER_PROBE_HOST: &str = "example.com";
+
+// Concorrência usada pela implementação padrão de resolve_batch
+const DEFAULT_BATCH_CONCURRENCY: usize = 50;
+
+#[derive(Debug, Clone)]
+pub struct ResolutionResult {
+    pub ips: Vec<String>,
+    pub status: ResolutionStatus,
+    pub records: HashMap<String, Vec<String>>,
+    // Menor TTL da resposta A/AAAA e tempo da consulta (ausente quando veio do cache)
+    pub ttl: Option<u32>,
+    pub resolve_ms: Option<u64>,
+}
+
+impl ResolutionResult {
+    pub fn new(ips: Vec<String>, status: ResolutionStatus) -> Self {
+        Self {
+            ips,
+            status,
+            records: HashMap::new(),
+            ttl: None,
+            resolve_ms: None,
+        }
+    }
+
+    fn apply(self, subdomain: &mut SubdomainResult) {
+        if !self.ips.is_empty() {
+            subdomain.resolved = true;
+            subdomain.ip_addresses = self.ips;
+        }
+        subdomain.status = Some(self.status);
+        subdomain.records = self.records;
+        subdomain.ttl = self.ttl;
+        subdomain.resolve_ms = self.resolve_ms;
+    }
+}
+
+// Backend de resolução usado pelo engine; permite trocar o resolvedor real por outro (ou por um mock nos testes)
+#[async_trait]
+pub trait DnsResolver: Send + Sync {
+    async fn resolve(&self, host: &str) -> ResolutionResult;
+
+    // Consulta PTR; devolve os nomes sem o ponto final
+    async fn resolve_ptr(&self, ip: IpAddr) -> Vec<String>;
+
+    async fn resolve_batch(&self, mut subdomains: Vec<SubdomainResult>) -> Result<Vec<SubdomainResult>, RustFinderError> {
+        // Nomes "*." (--include-wildcards) não são consultáveis; ficam sem status de resolução
+        let hosts: Vec<(usize, String)> = subdomains.iter()
+            .enumerate()
+            .filter(|(_, s)| !s.subdomain.starts_with("*."))
+            .map(|(idx, s)| (idx, s.subdomain.clone()))
+            .collect();
+
+        let mut lookups = futures::stream::iter(hosts)
+            .map(|(idx, host)| async move { (idx, self.resolve(&host).await) })
+            .buffer_unordered(DEFAULT_BATCH_CONCURRENCY);
+
+        while let Some((idx, result)) = lookups.next().await {
+            result.apply(&mut subdomains[idx]);
+        }
+
+        Ok(subdomains)
+    }
+}
+
+// Respostas A/AAAA já obtidas nesta execução, por hostname
+type DnsCache = Arc<Mutex<HashMap<String, ResolutionResult>>>;
+
 pub struct Resolver {
-    resolver: TokioAsyncResolver,
+    // Um único resolvedor, ou um por servidor com resolver.rotate_nameservers
+    resolvers: Vec<TokioAsyncResolver>,
+    next_resolver: AtomicUsize,
+    cache: Option<DnsCache>,
     semaphore: Arc<Semaphore>,
+    record_types: Vec<RecordType>,
     config: ResolverConfig,
 }
 
 impl Resolver {
     pub fn new(config: ResolverConfig) -> Result<Self, RustFinderError> {
-        let resolver = if config.use_system_resolver {
-            TokioAsyncResolver::tokio_from_system_conf()
-                .map_err(|e| RustFinderError::ResolutionError(format!("Failed to create system resolver: {}", e)))?
+        let resolvers = if config.use_system_resolver {
+            vec![TokioAsyncResolver::tokio_from_system_conf()
+                .map_err(|e| RustFinderError::ResolutionError(format!("Failed to create system resolver: {}", e)))?]
+        } else if config.rotate_nameservers && config.nameservers.len() > 1 {
+            config.nameservers.iter()
+                .map(|ns| Self::build_resolver(&config, std::slice::from_ref(ns)))
+                .collect::<Result<Vec<_>, _>>()?
         } else {
-            let mut resolver_config = DnsResolverConfig::new();
-            
-            for ns in &config.nameservers {
-                let socket_addr = SocketAddr::from_str(ns)
-                    .map_err(|e| RustFinderError::ConfigError(format!("Invalid nameserver address {}: {}", ns, e)))?;
-                resolver_config.add_name_server(trust_dns_resolver::config::NameServerConfig {
-                    socket_addr,
-                    protocol: trust_dns_resolver::config::Protocol::Udp,
-                    tls_dns_name: None,
-                    trust_negative_responses: false,
-                    bind_addr: None,
-                });
-            }
-            
-            let mut opts = ResolverOpts::default();
-            opts.timeout = config.timeout;
-            opts.attempts = 2;
-            
-            TokioAsyncResolver::tokio(resolver_config, opts)
+            vec![Self::build_resolver(&config, &config.nameservers)?]
+        };
+
+        let record_types = config.records.iter()
+            .map(|record| {
+                RecordType::from_str(&record.to_uppercase())
+                    .map_err(|e| RustFinderError::ConfigError(format!("Invalid DNS record type {}: {}", record, e)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let cache = if config.cache {
+            Some(Arc::new(Mutex::new(HashMap::new())))
+        } else {
+            None
         };
 
         Ok(Self {
-            resolver,
+            resolvers,
+            next_resolver: AtomicUsize::new(0),
+            cache,
             semaphore: Arc::new(Semaphore::new(config.threads)),
+            record_types,
             config,
         })
     }
 
-    pub async fn resolve_batch(&self, mut subdomains: Vec<SubdomainResult>) -> Result<Vec<SubdomainResult>, RustFinderError> {
+    fn build_resolver(config: &ResolverConfig, nameservers: &[String]) -> Result<TokioAsyncResolver, RustFinderError> {
+        let mut resolver_config = DnsResolverConfig::new();
+        for ns in nameservers {
+            Self::add_nameserver(config, &mut resolver_config, ns)?;
+        }
+
+        let mut opts = ResolverOpts::default();
+        opts.timeout = config.timeout;
+        opts.attempts = config.retries;
+
+        Ok(TokioAsyncResolver::tokio(resolver_config, opts))
+    }
+
+    // Rodízio entre os resolvedores; com um só, sempre o mesmo
+    fn next_index(&self) -> usize {
+        self.next_resolver.fetch_add(1, Ordering::Relaxed) % self.resolvers.len()
+    }
+
+    fn next_resolver(&self) -> &TokioAsyncResolver {
+        &self.resolvers[self.next_index()]
+    }
+
+    fn add_nameserver(config: &ResolverConfig, resolver_config: &mut DnsResolverConfig, ns: &str) -> Result<(), RustFinderError> {
+        let protocol = match config.protocol {
+            ResolverProtocol::Udp => Protocol::Udp,
+            ResolverProtocol::Tcp => Protocol::Tcp,
+            ResolverProtocol::Https => Protocol::Https,
+        };
+        let socket_addr = SocketAddr::from_str(ns)
+            .map_err(|e| RustFinderError::ConfigError(format!("Invalid nameserver address {}: {}", ns, e)))?;
+        Self::validate_nameserver(config, &socket_addr)?;
+
+        let mut ns_config = NameServerConfig::new(socket_addr, protocol);
+        ns_config.trust_negative_responses = false;
+        ns_config.tls_dns_name = config.tls_dns_name.clone();
+        resolver_config.add_name_server(ns_config);
+
+        // Respostas UDP truncadas são repetidas automaticamente pelo pool via TCP
+        if protocol == Protocol::Udp {
+            let mut tcp_config = NameServerConfig::new(socket_addr, Protocol::Tcp);
+            tcp_config.trust_negative_responses = false;
+            resolver_config.add_name_server(tcp_config);
+        }
+        Ok(())
+    }
+
+    // Consulta de teste em cada servidor configurado; devolve os que não responderam
+    pub async fn unreachable_nameservers(&self) -> Vec<String> {
+        if self.config.use_system_resolver {
+            return Vec::new();
+        }
+        let probes = self.config.nameservers.iter().map(|ns| async move {
+            let mut resolver_config = DnsResolverConfig::new();
+            if Self::add_nameserver(&self.config, &mut resolver_config, ns).is_err() {
+                return Some(ns.clone());
+            }
+            let mut opts = ResolverOpts::default();
+            opts.timeout = self.config.timeout;
+            opts.attempts = 0;
+            opts.cache_size = 0;
+
+            let resolver = TokioAsyncResolver::tokio(resolver_config, opts);
+            let reachable = match resolver.lookup_ip(NAMESERVER_PROBE_HOST).await {
+                Ok(_) => true,
+                // NXDOMAIN/NOERROR vazio ainda são respostas válidas do servidor
+                Err(e) => Self::status_from_error(&e) != ResolutionStatus::Error,
+            };
+            (!reachable).then(|| ns.clone())
+        });
+
+        futures::future::join_all(probes).await.into_iter().flatten().collect()
+    }
+
+    fn validate_nameserver(config: &ResolverConfig, socket_addr: &SocketAddr) -> Result<(), RustFinderError> {
+        match config.protocol {
+            ResolverProtocol::Https => {
+                if socket_addr.port() == 53 {
+                    return Err(RustFinderError::ConfigError(format!(
+                        "Nameserver {} uses port 53, which is not compatible with DNS-over-HTTPS", socket_addr
+                    )));
+                }
+                if config.tls_dns_name.is_none() {
+                    return Err(RustFinderError::ConfigError(
+                        "DNS-over-HTTPS requires resolver.tls_dns_name to be set".to_string()
+                    ));
+                }
+            }
+            ResolverProtocol::Udp | ResolverProtocol::Tcp => {
+                if socket_addr.port() == 443 {
+                    return Err(RustFinderError::ConfigError(format!(
+                        "Nameserver {} uses port 443, which looks like a DNS-over-HTTPS endpoint; set resolver.protocol to \"https\"", socket_addr
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn resolve_batch_keeping_source_ips(&self, mut subdomains: Vec<SubdomainResult>) -> Result<Vec<SubdomainResult>, RustFinderError> {
         let mut futures = FuturesUnordered::new();
+        let mut skipped = Vec::new();
         
         for (idx, subdomain) in subdomains.iter().enumerate() {
-            let resolver = self.resolver.clone();
+            if subdomain.subdomain.starts_with("*.") {
+                continue;
+            }
+            let resolver = self.next_resolver().clone();
+            let cache = self.cache.clone();
             let semaphore = self.semaphore.clone();
             let hostname = subdomain.subdomain.clone();
-            
+            let record_types = self.record_types.clone();
+
+            // IPs fornecidos pela própria fonte são mantidos, a menos que --force-resolve seja usado
+            let known_ips = if !self.config.force_resolve && subdomain.resolved && !subdomain.ip_addresses.is_empty() {
+                Some(subdomain.ip_addresses.clone())
+            } else {
+                None
+            };
+            if known_ips.is_some() && record_types.is_empty() {
+                skipped.push(idx);
+                continue;
+            }
+
             futures.push(async move {
                 let _permit = semaphore.acquire().await.unwrap();
-                let ips = Self::resolve_hostname(&resolver, &hostname).await;
-                (idx, ips)
+                let (answer, records) = tokio::join!(
+                    async {
+                        match known_ips {
+                            Some(ips) => ResolutionResult::new(ips, ResolutionStatus::Resolved),
+                            None => Self::resolve_hostname(&resolver, cache.as_ref(), &hostname).await,
+                        }
+                    },
+                    Self::lookup_records(&resolver, &hostname, &record_types),
+                );
+                (idx, ResolutionResult { records, ..answer })
             });
         }
 
-        while let Some((idx, ips)) = futures.next().await {
-            if !ips.is_empty() {
-                subdomains[idx].resolved = true;
-                subdomains[idx].ip_addresses = ips;
-            }
+        while let Some((idx, result)) = futures.next().await {
+            result.apply(&mut subdomains[idx]);
+        }
+
+        for idx in skipped {
+            subdomains[idx].status = Some(ResolutionStatus::Resolved);
         }
 
         Ok(subdomains)
     }
 
-    async fn resolve_hostname(resolver: &TokioAsyncResolver, hostname: &str) -> Vec<String> {
-        match resolver.lookup_ip(hostname).await {
+    async fn resolve_hostname(
+        resolver: &TokioAsyncResolver,
+        cache: Option<&DnsCache>,
+        hostname: &str,
+    ) -> ResolutionResult {
+        let key = hostname.to_lowercase();
+        if let Some(cached) = cache.and_then(|c| c.lock().unwrap().get(&key).cloned()) {
+            return ResolutionResult { resolve_ms: None, ..cached };
+        }
+
+        let answer = Self::lookup_hostname(resolver, hostname).await;
+        // Erros transitórios não são guardados, para que uma nova tentativa possa dar certo
+        if let Some(cache) = cache.filter(|_| answer.status != ResolutionStatus::Error) {
+            cache.lock().unwrap().insert(key, answer.clone());
+        }
+        answer
+    }
+
+    async fn lookup_hostname(resolver: &TokioAsyncResolver, hostname: &str) -> ResolutionResult {
+        let started = Instant::now();
+        let lookup = resolver.lookup_ip(hostname).await;
+        let resolve_ms = Some(started.elapsed().as_millis() as u64);
+
+        match lookup {
             Ok(lookup) => {
-                lookup.iter()
+                let ips: Vec<String> = lookup.iter()
                     .map(|ip| ip.to_string())
-                    .collect()
+                    .collect();
+                let status = if ips.is_empty() {
+                    ResolutionStatus::NoRecords
+                } else {
+                    ResolutionStatus::Resolved
+                };
+                // Inclui os CNAMEs da cadeia: vale o menor TTL
+                let ttl = lookup.as_lookup().records().iter().map(|record| record.ttl()).min();
+                ResolutionResult { ttl, resolve_ms, ..ResolutionResult::new(ips, status) }
             }
-            Err(_) => Vec::new(),
+            Err(e) => ResolutionResult { resolve_ms, ..ResolutionResult::new(Vec::new(), Self::status_from_error(&e)) },
+        }
+    }
+
+    fn status_from_error(error: &ResolveError) -> ResolutionStatus {
+        match error.kind() {
+            ResolveErrorKind::NoRecordsFound { response_code, .. } => match *response_code {
+                ResponseCode::NXDomain => ResolutionStatus::NxDomain,
+                ResponseCode::NoError => ResolutionStatus::NoRecords,
+                _ => ResolutionStatus::Error,
+            },
+            _ => ResolutionStatus::Error,
         }
     }
 
+    async fn lookup_records(
+        resolver: &TokioAsyncResolver,
+        hostname: &str,
+        record_types: &[RecordType],
+    ) -> HashMap<String, Vec<String>> {
+        let lookups = record_types.iter().map(|record_type| async move {
+            let values: Vec<String> = match resolver.lookup(hostname, *record_type).await {
+                Ok(lookup) => lookup.iter().map(|rdata| rdata.to_string()).collect(),
+                Err(_) => Vec::new(),
+            };
+            (record_type.to_string(), values)
+        });
+
+        futures::future::join_all(lookups).await
+            .into_iter()
+            .filter(|(_, values)| !values.is_empty())
+            .collect()
+    }
+
     pub async fn resolve_single(&self, hostname: &str) -> Result<Vec<IpAddr>, RustFinderError> {
         let _permit = self.semaphore.acquire().await
             .map_err(|e| RustFinderError::ResolutionError(format!("Failed to acquire semaphore: {}", e)))?;
             
-        self.resolver
+        self.next_resolver()
             .lookup_ip(hostname)
             .await
             .map(|lookup| lookup.iter().collect())
             .map_err(|e| RustFinderError::ResolutionError(format!("Failed to resolve {}: {}", hostname, e)))
     }
-}
\ No newline at end of file
+}
+
+#[async_trait]
+impl DnsResolver for Resolver {
+    async fn resolve(&self, host: &str) -> ResolutionResult {
+        let _permit = self.semaphore.acquire().await.unwrap();
+        let resolver = self.next_resolver();
+        let (answer, records) = tokio::join!(
+            Self::resolve_hostname(resolver, self.cache.as_ref(), host),
+            Self::lookup_records(resolver, host, &self.record_types),
+        );
+        ResolutionResult { records, ..answer }
+    }
+
+    async fn resolve_ptr(&self, ip: IpAddr) -> Vec<String> {
+        let _permit = self.semaphore.acquire().await.unwrap();
+        match self.next_resolver().reverse_lookup(ip).await {
+            Ok(lookup) => lookup.iter()
+                .map(|name| name.to_string().trim_end_matches('.').to_lowercase())
+                .collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    async fn resolve_batch(&self, subdomains: Vec<SubdomainResult>) -> Result<Vec<SubdomainResult>, RustFinderError> {
+        self.resolve_batch_keeping_source_ips(subdomains).await
+    }
+}
+
+// Resolvedor em memória para testes: hosts desconhecidos respondem NXDOMAIN
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct MockResolver {
+    answers: HashMap<String, Vec<String>>,
+}
+
+#[cfg(test)]
+impl MockResolver {
+    pub fn with_answer(mut self, host: &str, ips: &[&str]) -> Self {
+        self.answers.insert(host.to_lowercase(), ips.iter().map(|ip| ip.to_string()).collect());
+        self
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl DnsResolver for MockResolver {
+    async fn resolve_ptr(&self, ip: IpAddr) -> Vec<String> {
+        let ip = ip.to_string();
+        self.answers.iter()
+            .filter(|(_, ips)| ips.contains(&ip))
+            .map(|(host, _)| host.clone())
+            .collect()
+    }
+
+    async fn resolve(&self, host: &str) -> ResolutionResult {
+        match self.answers.get(&host.to_lowercase()) {
+            Some(ips) => ResolutionResult::new(ips.clone(), ResolutionStatus::Resolved),
+            None => ResolutionResult::new(Vec::new(), ResolutionStatus::NxDomain),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use trust_dns_resolver::proto::op::{Message, MessageType, OpCode};
+    use trust_dns_resolver::proto::rr::{RData, Record};
+
+    // Resposta vazia com o código dado, ecoando o id e a pergunta da consulta
+    fn reply(query: &Message, code: ResponseCode) -> Message {
+        let mut response = Message::new();
+        response
+            .set_id(query.id())
+            .set_message_type(MessageType::Response)
+            .set_op_code(OpCode::Query)
+            .set_recursion_desired(query.recursion_desired())
+            .set_recursion_available(true)
+            .set_response_code(code)
+            .add_queries(query.queries().to_vec());
+        response
+    }
+
+    // Servidor DNS UDP em 127.0.0.1 que responde cada consulta com `respond`
+    async fn udp_nameserver(respond: fn(&Message) -> Message) -> SocketAddr {
+        let socket = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = socket.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 4096];
+            while let Ok((len, peer)) = socket.recv_from(&mut buf).await {
+                if let Ok(query) = Message::from_vec(&buf[..len]) {
+                    let _ = socket.send_to(&respond(&query).to_vec().unwrap(), peer).await;
+                }
+            }
+        });
+        addr
+    }
+
+    // Servidor DNS TCP no mesmo endereço, com o prefixo de tamanho de 2 bytes em cada mensagem
+    async fn tcp_nameserver(addr: SocketAddr, respond: fn(&Message) -> Message) {
+        let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut len = [0u8; 2];
+                    while stream.read_exact(&mut len).await.is_ok() {
+                        let mut buf = vec![0u8; u16::from_be_bytes(len) as usize];
+                        if stream.read_exact(&mut buf).await.is_err() {
+                            break;
+                        }
+                        let query = match Message::from_vec(&buf) {
+                            Ok(query) => query,
+                            Err(_) => break,
+                        };
+                        let response = respond(&query).to_vec().unwrap();
+                        let _ = stream.write_all(&(response.len() as u16).to_be_bytes()).await;
+                        let _ = stream.write_all(&response).await;
+                    }
+                });
+            }
+        });
+    }
+
+    #[tokio::test]
+    async fn test_mock_resolver_batch() {
+        let resolver = MockResolver::default().with_answer("www.example.com", &["192.0.2.1"]);
+        let subdomains = ["www.example.com", "missing.example.com", "*.example.com"]
+            .iter()
+            .map(|host| SubdomainResult {
+                subdomain: host.to_string(),
+                ..Default::default()
+            })
+            .collect();
+
+        let results = resolver.resolve_batch(subdomains).await.unwrap();
+        assert!(results[0].resolved);
+        assert_eq!(results[0].ip_addresses, vec!["192.0.2.1"]);
+        assert!(!results[1].resolved);
+        assert_eq!(results[1].status, Some(ResolutionStatus::NxDomain));
+        assert_eq!(results[2].status, None);
+    }
+
+    #[tokio::test]
+    async fn test_dns_cache_hit_and_miss() {
+        // Servidor local que responde SERVFAIL: nenhuma consulta sai para a rede
+        let nameserver = udp_nameserver(|query| reply(query, ResponseCode::ServFail)).await;
+        let config = ResolverConfig {
+            nameservers: vec![nameserver.to_string()],
+            timeout: std::time::Duration::from_secs(2),
+            retries: 0,
+            ..Default::default()
+        };
+        let resolver = Resolver::new(config).unwrap();
+        let cache: DnsCache = Default::default();
+        cache.lock().unwrap().insert(
+            "www.example.com".to_string(),
+            ResolutionResult::new(vec!["192.0.2.10".to_string()], ResolutionStatus::Resolved),
+        );
+
+        let hit = Resolver::resolve_hostname(&resolver.resolvers[0], Some(&cache), "WWW.example.com").await;
+        assert_eq!(hit.ips, vec!["192.0.2.10"]);
+        assert_eq!(hit.resolve_ms, None);
+
+        // Falha transitória não entra no cache
+        let miss = Resolver::resolve_hostname(&resolver.resolvers[0], Some(&cache), "api.example.com").await;
+        assert_eq!(miss.status, ResolutionStatus::Error);
+        assert!(!cache.lock().unwrap().contains_key("api.example.com"));
+    }
+
+    #[tokio::test]
+    async fn test_truncated_udp_answer_retries_over_tcp() {
+        // Pelo UDP só vem a resposta truncada; a completa está no TCP da mesma porta
+        let nameserver = udp_nameserver(|query| {
+            let mut response = reply(query, ResponseCode::NoError);
+            response.set_truncated(true);
+            response
+        })
+        .await;
+        tcp_nameserver(nameserver, |query| {
+            let mut response = reply(query, ResponseCode::NoError);
+            if let Some(question) = query.queries().iter().find(|q| q.query_type() == RecordType::A) {
+                let ip = Ipv4Addr::new(192, 0, 2, 53);
+                response.add_answer(Record::from_rdata(question.name().clone(), 60, RData::A(ip.into())));
+            }
+            response
+        })
+        .await;
+
+        let config = ResolverConfig {
+            nameservers: vec![nameserver.to_string()],
+            timeout: std::time::Duration::from_secs(2),
+            retries: 0,
+            ..Default::default()
+        };
+        let resolver = Resolver::new(config).unwrap();
+        let answer = resolver.resolve("www.example.com").await;
+        assert_eq!(answer.status, ResolutionStatus::Resolved);
+        assert_eq!(answer.ips, vec!["192.0.2.53"]);
+    }
+
+    #[tokio::test]
+    async fn test_wildcard_names_are_not_queried() {
+        let config = ResolverConfig {
+            nameservers: vec!["192.0.2.1:53".to_string()],
+            ..Default::default()
+        };
+        let resolver = Resolver::new(config).unwrap();
+        let subdomains = vec![SubdomainResult {
+            subdomain: "*.example.com".to_string(),
+            ..Default::default()
+        }];
+
+        let results = resolver.resolve_batch(subdomains).await.unwrap();
+        assert!(!results[0].resolved);
+        assert_eq!(results[0].status, None);
+    }
+
+    #[tokio::test]
+    async fn test_rotate_nameservers() {
+        let config = ResolverConfig {
+            nameservers: vec!["192.0.2.1:53".to_string(), "192.0.2.2:53".to_string(), "192.0.2.3:53".to_string()],
+            rotate_nameservers: true,
+            ..Default::default()
+        };
+        let resolver = Resolver::new(config.clone()).unwrap();
+        assert_eq!(resolver.resolvers.len(), 3);
+        let order: Vec<usize> = (0..4).map(|_| resolver.next_index()).collect();
+        assert_eq!(order, vec![0, 1, 2, 0]);
+
+        let resolver = Resolver::new(ResolverConfig { rotate_nameservers: false, ..config }).unwrap();
+        assert_eq!(resolver.resolvers.len(), 1);
+        assert_eq!(resolver.next_index(), 0);
+    }
+}
@@ -1,12 +1,23 @@
 // src/session.rs
-use crate::types::{Config, RustFinderError};
+use crate::types::{BackoffStrategy, Config, HttpVersion, KeyRotation, RustFinderError, TlsVersion};
 use governor::{Jitter, Quota};
 use reqwest::Client;
 use std::collections::HashMap;
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use rand::seq::SliceRandom;
 
+// Com até este número de requisições restantes, a próxima espera o reset da cota
+const RATE_LIMIT_LOW_REMAINING: u64 = 1;
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(15 * 60);
+// Valores de x-ratelimit-reset acima disto são timestamps Unix; abaixo, segundos até o reset
+const RATE_LIMIT_RESET_EPOCH_THRESHOLD: u64 = 1_000_000_000;
+
+// Maior linha aceita por read_lines; uma resposta sem quebras de linha não cresce sem limite
+const MAX_LINE_BYTES: usize = 64 * 1024;
+// --http-probe: uma tentativa curta por URL, sem seguir redirecionamentos
+const HTTP_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
 type MyRateLimiter = governor::DefaultKeyedRateLimiter<String>;
 
 const USER_AGENTS: &[&str] = &[
@@ -38,9 +49,20 @@ const USER_AGENTS: &[&str] = &[
 #[derive(Clone)]
 pub struct Session {
     pub client: Client,
+    probe_client: Client,
     rate_limiters: Arc<HashMap<String, Arc<governor::DefaultDirectRateLimiter>>>,
+    // Limite de requisições por segundo somando todas as fontes (--rate-limit)
+    global_limiter: Option<Arc<governor::DefaultDirectRateLimiter>>,
+    // Instante de reset informado pelos cabeçalhos x-ratelimit-*, por fonte e chave de API
+    rate_limit_resets: Arc<Mutex<HashMap<(String, u64), Instant>>>,
+    // Resets mais distantes que isto falham na hora, em vez de estourar o timeout da fonte
+    max_rate_limit_wait: Duration,
     retry_attempts: u32,
+    timeout_retries: u32,
     retry_delay_ms: u64,
+    retry_backoff: BackoffStrategy,
+    max_retry_delay_ms: u64,
+    max_response_bytes: u64,
     user_agent: String,
 }
 
@@ -54,9 +76,9 @@ impl Session {
             .user_agent(user_agent.clone())
             .gzip(true)
             .deflate(true)
-            .connect_timeout(Duration::from_secs(10))
-            .pool_idle_timeout(Duration::from_secs(90))
-            .pool_max_idle_per_host(10)
+            .connect_timeout(config.connect_timeout)
+            .pool_idle_timeout(config.pool_idle_timeout)
+            .pool_max_idle_per_host(config.pool_max_idle_per_host)
 
             .default_headers({
                 let mut headers = reqwest::header::HeaderMap::new();
@@ -69,35 +91,118 @@ impl Session {
                 headers
             });
 
-        if let Some(proxy_url) = &config.proxy {
-            let proxy = reqwest::Proxy::all(proxy_url)
-                .map_err(|e| RustFinderError::ConfigError(format!("Invalid proxy URL: {}", e)))?;
-            client_builder = client_builder.proxy(proxy);
+        client_builder = match config.http_version {
+            HttpVersion::Auto => client_builder,
+            HttpVersion::Http1 => client_builder.http1_only(),
+            HttpVersion::Http2 => client_builder.http2_prior_knowledge(),
+        };
+
+        if let Some(version) = config.min_tls_version {
+            client_builder = client_builder.min_tls_version(match version {
+                TlsVersion::Tls1_0 => reqwest::tls::Version::TLS_1_0,
+                TlsVersion::Tls1_1 => reqwest::tls::Version::TLS_1_1,
+                TlsVersion::Tls1_2 => reqwest::tls::Version::TLS_1_2,
+                TlsVersion::Tls1_3 => reqwest::tls::Version::TLS_1_3,
+            });
         }
 
-        let client = client_builder.build()
+        let client = Self::with_proxies(client_builder, config)?.build()
+            .map_err(|e| RustFinderError::ConfigError(format!("Failed to build HTTP client: {}", e)))?;
+
+        let probe_builder = Client::builder()
+            .timeout(HTTP_PROBE_TIMEOUT)
+            .connect_timeout(config.connect_timeout.min(HTTP_PROBE_TIMEOUT))
+            .user_agent(user_agent.clone())
+            .redirect(reqwest::redirect::Policy::none());
+        let probe_client = Self::with_proxies(probe_builder, config)?.build()
             .map_err(|e| RustFinderError::ConfigError(format!("Failed to build HTTP client: {}", e)))?;
 
         let mut rate_limiters = HashMap::new();
 
         for (source, rate_limit) in &config.rate_limits {
             if let Some(limit) = rate_limit {
-                let quota = Quota::per_second(std::num::NonZeroU32::new(*limit).unwrap())
+                // Em round-robin cada chave tem a própria cota, então o limite escala com elas
+                let limit = match config.key_rotation {
+                    KeyRotation::RoundRobin => {
+                        let keys = config.api_keys.get(source).map(|k| k.len()).unwrap_or(0).max(1);
+                        limit.saturating_mul(keys as u32)
+                    }
+                    KeyRotation::Failover => *limit,
+                };
+                let quota = Quota::per_second(std::num::NonZeroU32::new(limit).unwrap())
                     .allow_burst(std::num::NonZeroU32::new(1).unwrap());
                 let limiter = Arc::new(governor::RateLimiter::direct(quota));
                 rate_limiters.insert(source.clone(), limiter);
             }
         }
 
+        let global_limiter = config.global_rate_limit
+            .and_then(std::num::NonZeroU32::new)
+            .map(|limit| {
+                let quota = Quota::per_second(limit).allow_burst(std::num::NonZeroU32::new(1).unwrap());
+                Arc::new(governor::RateLimiter::direct(quota))
+            });
+
         Ok(Session {
             client,
+            probe_client,
             rate_limiters: Arc::new(rate_limiters),
+            global_limiter,
+            rate_limit_resets: Arc::new(Mutex::new(HashMap::new())),
+            // Metade do timeout da fonte, para sobrar tempo para a própria requisição
+            max_rate_limit_wait: MAX_RATE_LIMIT_WAIT.min(config.source_timeout / 2),
             retry_attempts: config.retry_attempts,
+            timeout_retries: config.timeout_retries,
             retry_delay_ms: config.retry_delay_ms,
+            retry_backoff: config.retry_backoff,
+            max_retry_delay_ms: config.max_retry_delay_ms,
+            max_response_bytes: config.max_response_bytes,
             user_agent,
         })
     }
 
+    // Proxies específicos por esquema têm prioridade sobre o proxy geral
+    fn with_proxies(
+        mut client_builder: reqwest::ClientBuilder,
+        config: &Config,
+    ) -> Result<reqwest::ClientBuilder, RustFinderError> {
+        if let Some(proxy_url) = &config.proxy_http {
+            Self::validate_proxy_url(proxy_url)?;
+            let proxy = reqwest::Proxy::http(proxy_url)
+                .map_err(|e| RustFinderError::ConfigError(format!("Invalid HTTP proxy URL {}: {}", proxy_url, e)))?;
+            client_builder = client_builder.proxy(proxy);
+        }
+
+        if let Some(proxy_url) = &config.proxy_https {
+            Self::validate_proxy_url(proxy_url)?;
+            let proxy = reqwest::Proxy::https(proxy_url)
+                .map_err(|e| RustFinderError::ConfigError(format!("Invalid HTTPS proxy URL {}: {}", proxy_url, e)))?;
+            client_builder = client_builder.proxy(proxy);
+        }
+
+        if let Some(proxy_url) = &config.proxy {
+            Self::validate_proxy_url(proxy_url)?;
+            let proxy = reqwest::Proxy::all(proxy_url)
+                .map_err(|e| RustFinderError::ConfigError(format!("Invalid proxy URL {}: {}", proxy_url, e)))?;
+            client_builder = client_builder.proxy(proxy);
+        }
+
+        Ok(client_builder)
+    }
+
+    fn validate_proxy_url(proxy_url: &str) -> Result<(), RustFinderError> {
+        let url = url::Url::parse(proxy_url)
+            .map_err(|e| RustFinderError::ConfigError(format!("Invalid proxy URL {}: {}", proxy_url, e)))?;
+
+        match url.scheme() {
+            "http" | "https" | "socks5" | "socks5h" => Ok(()),
+            scheme => Err(RustFinderError::ConfigError(format!(
+                "Unsupported proxy scheme '{}' in {} (expected http, https, socks5 or socks5h)",
+                scheme, proxy_url
+            ))),
+        }
+    }
+
     pub fn get_random_user_agent() -> String {
         let mut rng = rand::thread_rng();
         USER_AGENTS.choose(&mut rng)
@@ -121,6 +226,20 @@ impl Session {
         self.send_request_with_retry(self.client.get(url), source_name).await
     }
 
+    // HEAD de verificação (--http-probe): uma única tentativa, timeout curto e sem seguir
+    // redirecionamentos, para que um 3xx chegue como está; os limites de taxa valem como em get
+    pub async fn head(&self, url: &str, source_name: &str) -> Result<reqwest::Response, RustFinderError> {
+        self.check_rate_limit(source_name).await?;
+        if let Some(limiter) = &self.global_limiter {
+            limiter.until_ready().await;
+        }
+        self.probe_client
+            .head(url)
+            .send()
+            .await
+            .map_err(|e| RustFinderError::NetworkError(e.to_string()))
+    }
+
     pub async fn post(&self, url: &str, body: String, source_name: &str) -> Result<reqwest::Response, RustFinderError> {
         self.send_request_with_retry(
             self.client.post(url)
@@ -135,7 +254,7 @@ impl Session {
         T: serde::de::DeserializeOwned,
     {
         let response = self.get(url, source_name).await?;
-        let text = response.text().await.map_err(|e| RustFinderError::NetworkError(e.to_string()))?;
+        let text = self.read_text(response).await?;
 
         serde_json::from_str(&text).map_err(|e| {
             RustFinderError::JsonParseError(e.to_string(), text)
@@ -156,21 +275,127 @@ impl Session {
             )));
         }
 
-        response
-            .json::<R>()
-            .await
-            .map_err(|e| RustFinderError::ParseError(e.to_string()))
+        let text = self.read_text(response).await?;
+        serde_json::from_str(&text).map_err(|e| RustFinderError::ParseError(e.to_string()))
+    }
+
+    pub async fn read_text(&self, response: reqwest::Response) -> Result<String, RustFinderError> {
+        let bytes = self.read_bytes(response).await?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    // Igual a read_text, mas com um limite menor que o global (nunca maior)
+    pub async fn read_text_limited(&self, response: reqwest::Response, limit: u64) -> Result<String, RustFinderError> {
+        let bytes = self.read_bytes_limited(response, limit.min(self.max_response_bytes)).await?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    pub async fn read_bytes(&self, response: reqwest::Response) -> Result<Vec<u8>, RustFinderError> {
+        self.read_bytes_limited(response, self.max_response_bytes).await
+    }
+
+    async fn read_bytes_limited(&self, mut response: reqwest::Response, limit: u64) -> Result<Vec<u8>, RustFinderError> {
+
+        if let Some(length) = response.content_length() {
+            if length > limit {
+                return Err(RustFinderError::NetworkError(format!(
+                    "Response from {} too large: {} bytes (limit {})",
+                    response.url(), length, limit
+                )));
+            }
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|e| RustFinderError::NetworkError(e.to_string()))? {
+            if body.len() as u64 + chunk.len() as u64 > limit {
+                return Err(RustFinderError::NetworkError(format!(
+                    "Response from {} exceeded size limit of {} bytes",
+                    response.url(), limit
+                )));
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        Ok(body)
+    }
+
+    // Leitura linha a linha para respostas grandes (índices CDX), com o mesmo limite de read_bytes
+    pub fn read_lines(&self, response: reqwest::Response) -> LineReader {
+        LineReader {
+            response,
+            pending: Vec::new(),
+            read: 0,
+            limit: self.max_response_bytes,
+            done: false,
+        }
+    }
+
+    fn backoff_delay_ms(&self, attempts: u32) -> u64 {
+        let delay = match self.retry_backoff {
+            BackoffStrategy::Constant => self.retry_delay_ms,
+            BackoffStrategy::Linear => self.retry_delay_ms.saturating_mul(attempts as u64),
+            BackoffStrategy::Exponential => {
+                let factor = 2u64.checked_pow(attempts.saturating_sub(1)).unwrap_or(u64::MAX);
+                self.retry_delay_ms.saturating_mul(factor)
+            }
+        };
+        delay.min(self.max_retry_delay_ms)
+    }
+
+    // Guarda o reset da cota quando a resposta indica que restam poucas requisições
+    fn record_rate_limit(&self, source_name: &str, credential: u64, headers: &reqwest::header::HeaderMap) {
+        let now_epoch = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        if let Some(wait) = rate_limit_wait(headers, now_epoch) {
+            let wait = wait.min(MAX_RATE_LIMIT_WAIT);
+            log::debug!("[{}] Rate limit almost exhausted; next request waits {:?}", source_name, wait);
+            if let Ok(mut resets) = self.rate_limit_resets.lock() {
+                resets.insert((source_name.to_string(), credential), Instant::now() + wait);
+            }
+        }
+    }
+
+    // Um reset além de max_rate_limit_wait vira RateLimitExceeded, e a rotação passa para a próxima chave
+    async fn wait_for_rate_limit_reset(&self, source_name: &str, credential: u64) -> Result<(), RustFinderError> {
+        let reset = self.rate_limit_resets
+            .lock()
+            .ok()
+            .and_then(|resets| resets.get(&(source_name.to_string(), credential)).copied());
+        if let Some(wait) = reset.and_then(|reset| reset.checked_duration_since(Instant::now())) {
+            if wait > self.max_rate_limit_wait {
+                return Err(RustFinderError::RateLimitExceeded {
+                    source_name: source_name.to_string(),
+                    message: format!("Quota resets in {:?}, longer than the source timeout allows", wait),
+                });
+            }
+            log::warn!("[{}] Rate limit almost exhausted. Waiting {:?} for reset", source_name, wait);
+            tokio::time::sleep(wait).await;
+        }
+        Ok(())
     }
 
     pub async fn send_request_with_retry(&self, request_builder: reqwest::RequestBuilder, source_name: &str) -> Result<reqwest::Response, RustFinderError> {
         let mut attempts = 0;
+        // Timeouts têm contagem própria: uma fonte lenta raramente melhora insistindo
+        let mut timeouts = 0;
         loop {
             attempts += 1;
             let request = request_builder.try_clone()
                 .ok_or_else(|| RustFinderError::NetworkError("Failed to clone request builder".to_string()))?;
-            
+
+            let credential = request.try_clone()
+                .and_then(|request| request.build().ok())
+                .map(|request| credential_fingerprint(&request))
+                .unwrap_or_default();
+            self.wait_for_rate_limit_reset(source_name, credential).await?;
+
+            // Cada tentativa, inclusive os retries, conta para o limite global
+            if let Some(limiter) = &self.global_limiter {
+                limiter.until_ready().await;
+            }
+
             match request.send().await {
                 Ok(response) => {
+                    self.record_rate_limit(source_name, credential, response.headers());
                     if response.status().is_success() {
                         return Ok(response);
                     } else if response.status().as_u16() == 429 || response.status().is_server_error() {
@@ -179,13 +404,17 @@ impl Session {
                             .and_then(|h| h.to_str().ok())
                             .and_then(|s| s.parse::<u64>().ok());
 
+                        // Retry-After também respeita max_retry_delay_ms (ex.: 86400 não vira um dia de espera)
                         let delay = if let Some(seconds) = retry_after {
-                            Duration::from_secs(seconds)
+                            Duration::from_secs(seconds).min(Duration::from_millis(self.max_retry_delay_ms))
                         } else {
 
-                            let base_delay = self.retry_delay_ms * 2u64.pow(attempts - 1);
-                            let jitter = rand::random::<u64>() % (base_delay / 4);
-                            Duration::from_millis(base_delay + jitter)
+                            let base_delay = self.backoff_delay_ms(attempts);
+                            let jitter = match base_delay / 4 {
+                                0 => 0,
+                                max_jitter => rand::random::<u64>() % max_jitter,
+                            };
+                            Duration::from_millis((base_delay + jitter).min(self.max_retry_delay_ms))
                         };
 
                         log::warn!("[{}] Rate limit hit or server error ({}). Retrying in {:?}. Attempt {}/{}", 
@@ -206,15 +435,266 @@ impl Session {
                         )));
                     }
                 },
+                Err(e) if e.is_timeout() => {
+                    // Não consome as tentativas de retry_attempts
+                    attempts -= 1;
+                    timeouts += 1;
+                    if timeouts > self.timeout_retries {
+                        return Err(RustFinderError::NetworkError(format!(
+                            "Request timed out after {} retries: {}",
+                            self.timeout_retries, e
+                        )));
+                    }
+                    log::warn!("[{}] Request timed out. Retry {}/{}", source_name, timeouts, self.timeout_retries);
+                    let delay = Duration::from_millis(self.backoff_delay_ms(timeouts));
+                    tokio::time::sleep(delay).await;
+                }
                 Err(e) => {
                     log::warn!("[{}] Network error: {}. Attempt {}/{}", source_name, e, attempts, self.retry_attempts);
                     if attempts >= self.retry_attempts {
                         return Err(RustFinderError::NetworkError(format!("Max retries ({}) exceeded for network error: {}", self.retry_attempts, e)));
                     }
-                    let delay = Duration::from_millis(self.retry_delay_ms * 2u64.pow(attempts - 1));
+                    let delay = Duration::from_millis(self.backoff_delay_ms(attempts));
                     tokio::time::sleep(delay).await;
                 }
             }
         }
     }
-}
\ No newline at end of file
+}
+
+// Espera sugerida pelos cabeçalhos x-ratelimit-remaining/x-ratelimit-reset (ou a variante sem "x-")
+fn rate_limit_wait(headers: &reqwest::header::HeaderMap, now_epoch: u64) -> Option<Duration> {
+    let header = |names: [&str; 2]| {
+        names
+            .iter()
+            .find_map(|name| headers.get(*name)?.to_str().ok()?.trim().parse::<u64>().ok())
+    };
+    let remaining = header(["x-ratelimit-remaining", "ratelimit-remaining"])?;
+    if remaining > RATE_LIMIT_LOW_REMAINING {
+        return None;
+    }
+    let reset = header(["x-ratelimit-reset", "ratelimit-reset"])?;
+    let seconds = if reset > RATE_LIMIT_RESET_EPOCH_THRESHOLD {
+        reset.saturating_sub(now_epoch)
+    } else {
+        reset
+    };
+    (seconds > 0).then(|| Duration::from_secs(seconds))
+}
+
+// Identifica a chave de API da requisição (cabeçalhos de autenticação ou parâmetros como key/token),
+// para que a cota de uma chave não faça as outras esperarem
+fn credential_fingerprint(request: &reqwest::Request) -> u64 {
+    use std::hash::{Hash, Hasher};
+    const AUTH_HEADERS: [&str; 5] = ["authorization", "x-apikey", "x-api-key", "apikey", "x-key"];
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    for name in AUTH_HEADERS {
+        if let Some(value) = request.headers().get(name) {
+            value.as_bytes().hash(&mut hasher);
+        }
+    }
+    for (name, value) in request.url().query_pairs() {
+        let name = name.to_lowercase();
+        if name.contains("key") || name.contains("token") || name == "email" {
+            value.hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
+pub struct LineReader {
+    response: reqwest::Response,
+    pending: Vec<u8>,
+    read: u64,
+    limit: u64,
+    done: bool,
+}
+
+impl LineReader {
+    // Próxima linha (sem o \n); None no fim do corpo
+    pub async fn next_line(&mut self) -> Result<Option<String>, RustFinderError> {
+        loop {
+            if let Some(end) = self.pending.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = self.pending.drain(..=end).collect();
+                return Ok(Some(String::from_utf8_lossy(&line[..end]).into_owned()));
+            }
+            if self.done {
+                if self.pending.is_empty() {
+                    return Ok(None);
+                }
+                let line = std::mem::take(&mut self.pending);
+                return Ok(Some(String::from_utf8_lossy(&line).into_owned()));
+            }
+
+            match self.response.chunk().await.map_err(|e| RustFinderError::NetworkError(e.to_string()))? {
+                Some(chunk) => {
+                    self.read += chunk.len() as u64;
+                    if self.read > self.limit {
+                        return Err(RustFinderError::NetworkError(format!(
+                            "Response from {} exceeded size limit of {} bytes",
+                            self.response.url(), self.limit
+                        )));
+                    }
+                    self.pending.extend_from_slice(&chunk);
+                    if self.pending.len() > MAX_LINE_BYTES && !self.pending.contains(&b'\n') {
+                        return Err(RustFinderError::NetworkError(format!(
+                            "Response from {} has a line longer than {} bytes",
+                            self.response.url(), MAX_LINE_BYTES
+                        )));
+                    }
+                }
+                None => self.done = true,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_delay_is_capped() {
+        let config = Config {
+            retry_attempts: 10,
+            retry_delay_ms: 1000,
+            ..Config::default()
+        };
+        let session = Session::new(&config).unwrap();
+
+        assert_eq!(session.backoff_delay_ms(1), 1000);
+        assert_eq!(session.backoff_delay_ms(10), 30_000);
+        assert_eq!(session.backoff_delay_ms(100), 30_000);
+
+        let constant = Session::new(&Config { retry_backoff: BackoffStrategy::Constant, ..config.clone() }).unwrap();
+        assert_eq!(constant.backoff_delay_ms(1), 1000);
+        assert_eq!(constant.backoff_delay_ms(5), 1000);
+
+        let linear = Session::new(&Config { retry_backoff: BackoffStrategy::Linear, ..config }).unwrap();
+        assert_eq!(linear.backoff_delay_ms(1), 1000);
+        assert_eq!(linear.backoff_delay_ms(3), 3000);
+        assert_eq!(linear.backoff_delay_ms(100), 30_000);
+    }
+
+    #[test]
+    fn test_rate_limit_wait_from_headers() {
+        let headers = |remaining: &str, reset: &str| {
+            let mut headers = reqwest::header::HeaderMap::new();
+            headers.insert("x-ratelimit-remaining", remaining.parse().unwrap());
+            headers.insert("x-ratelimit-reset", reset.parse().unwrap());
+            headers
+        };
+        let now = 1_700_000_000;
+
+        assert_eq!(rate_limit_wait(&headers("0", "1700000042"), now), Some(Duration::from_secs(42)));
+        assert_eq!(rate_limit_wait(&headers("1", "30"), now), Some(Duration::from_secs(30)));
+        assert_eq!(rate_limit_wait(&headers("25", "1700000042"), now), None);
+        assert_eq!(rate_limit_wait(&headers("0", "1699999999"), now), None);
+        assert_eq!(rate_limit_wait(&reqwest::header::HeaderMap::new(), now), None);
+    }
+
+    #[test]
+    fn test_credential_fingerprint_ignores_paging() {
+        let client = Client::new();
+        let fingerprint = |key: &str, page: &str| {
+            let request = client
+                .get("https://api.example.com/search")
+                .query(&[("key", key), ("page", page)])
+                .header("Accept", "application/json")
+                .build()
+                .unwrap();
+            credential_fingerprint(&request)
+        };
+        assert_eq!(fingerprint("k1", "1"), fingerprint("k1", "2"));
+        assert_ne!(fingerprint("k1", "1"), fingerprint("k2", "1"));
+    }
+
+    #[tokio::test]
+    async fn test_distant_reset_fails_fast_per_key() {
+        let session = Session::new(&Config { source_timeout: Duration::from_secs(30), ..Config::default() }).unwrap();
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", "0".parse().unwrap());
+        headers.insert("x-ratelimit-reset", "600".parse().unwrap());
+        session.record_rate_limit("shodan", 1, &headers);
+
+        let err = session.wait_for_rate_limit_reset("shodan", 1).await.unwrap_err();
+        assert!(matches!(err, RustFinderError::RateLimitExceeded { .. }));
+        // Outra chave da mesma fonte não herda a espera
+        assert!(session.wait_for_rate_limit_reset("shodan", 2).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_is_capped() {
+        let mut server = mockito::Server::new_async().await;
+        let throttled = server
+            .mock("GET", "/")
+            .with_status(429)
+            .with_header("Retry-After", "86400")
+            .expect(2)
+            .create_async()
+            .await;
+
+        let config = Config { retry_attempts: 2, max_retry_delay_ms: 10, ..Config::default() };
+        let session = Session::new(&config).unwrap();
+        let request = session.client.get(server.url());
+        let result = tokio::time::timeout(Duration::from_secs(5), session.send_request_with_retry(request, "test")).await;
+
+        assert!(matches!(result, Ok(Err(RustFinderError::RateLimitExceeded { .. }))));
+        throttled.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_read_lines_enforces_limits() {
+        let mut server = mockito::Server::new_async().await;
+        let _lines = server.mock("GET", "/lines").with_body("a\nb\n\nc").create_async().await;
+        let _big = server.mock("GET", "/big").with_body("x\n".repeat(600)).create_async().await;
+        let _long = server.mock("GET", "/long").with_body("y".repeat(MAX_LINE_BYTES + 1)).create_async().await;
+
+        let session = Session::new(&Config { max_response_bytes: 1000, ..Config::default() }).unwrap();
+        let read_all = |path: &'static str| {
+            let session = session.clone();
+            let url = format!("{}{}", server.url(), path);
+            async move {
+                let response = session.client.get(&url).send().await.unwrap();
+                let mut reader = session.read_lines(response);
+                let mut lines = Vec::new();
+                while let Some(line) = reader.next_line().await? {
+                    lines.push(line);
+                }
+                Ok::<_, RustFinderError>(lines)
+            }
+        };
+
+        assert_eq!(read_all("/lines").await.unwrap(), vec!["a", "b", "", "c"]);
+        assert!(read_all("/big").await.is_err());
+
+        let session = Session::new(&Config { max_response_bytes: 1 << 20, ..Config::default() }).unwrap();
+        let url = format!("{}/long", server.url());
+        let response = session.client.get(&url).send().await.unwrap();
+        assert!(session.read_lines(response).next_line().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_head_does_not_follow_redirects() {
+        let mut server = mockito::Server::new_async().await;
+        let redirect = server
+            .mock("HEAD", "/")
+            .with_status(301)
+            .with_header("location", "https://www.example.com/")
+            .expect(1)
+            .create_async()
+            .await;
+        let failing = server.mock("HEAD", "/down").with_status(503).expect(1).create_async().await;
+
+        let session = Session::new(&Config::default()).unwrap();
+        let response = session.head(&server.url(), "http-probe").await.unwrap();
+        assert_eq!(response.status().as_u16(), 301);
+        assert_eq!(response.headers()["location"], "https://www.example.com/");
+
+        // Sem novas tentativas: um 5xx volta na primeira resposta
+        let response = session.head(&format!("{}/down", server.url()), "http-probe").await.unwrap();
+        assert_eq!(response.status().as_u16(), 503);
+        redirect.assert_async().await;
+        failing.assert_async().await;
+    }
+}
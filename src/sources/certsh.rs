@@ -1,20 +1,26 @@
 // src/sources/crtsh.rs
 use crate::session::Session;
 use crate::sources::Source;
-use crate::types::{RustFinderError, SourceInfo, SubdomainResult};
+use crate::types::{RustFinderError, SourceCategory, SourceInfo, SubdomainResult};
 use async_trait::async_trait;
 use serde::Deserialize;
-use std::collections::HashSet;
+use std::collections::HashMap;
+use std::time::Duration;
 
 #[derive(Debug, Deserialize)]
 struct CrtShResponse {
     id: u64,
     name_value: String,
+    #[serde(default)]
+    not_before: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct CrtShSource {
     name: String,
+    timeout: Duration,
+    max_response_bytes: u64,
+    include_wildcards: bool,
 }
 
 impl Default for CrtShSource {
@@ -25,7 +31,85 @@ impl Default for CrtShSource {
 
 impl CrtShSource {
     pub fn new() -> Self {
-        Self { name: "crtsh".to_string() }
+        Self {
+            name: "crtsh".to_string(),
+            timeout: Duration::from_secs(20),
+            max_response_bytes: 10 * 1024 * 1024,
+            include_wildcards: false,
+        }
+    }
+
+    pub fn with_limits(mut self, timeout: Duration, max_response_bytes: u64) -> Self {
+        self.timeout = timeout;
+        self.max_response_bytes = max_response_bytes;
+        self
+    }
+
+    pub fn with_include_wildcards(mut self, include_wildcards: bool) -> Self {
+        self.include_wildcards = include_wildcards;
+        self
+    }
+
+    fn source_error(&self, message: String) -> RustFinderError {
+        RustFinderError::SourceError {
+            source_name: self.name.to_string(),
+            message,
+        }
+    }
+
+    // O crt.sh costuma responder 502/504 (às vezes com status 200) com uma página HTML
+    fn map_error(&self, error: RustFinderError) -> RustFinderError {
+        match error {
+            RustFinderError::RateLimitExceeded { message, .. }
+                if message.contains("502") || message.contains("503") || message.contains("504") =>
+            {
+                self.source_error(format!("crt.sh unavailable: {}", message))
+            }
+            RustFinderError::NetworkError(message) if message.contains("size limit") || message.contains("too large") => {
+                self.source_error(format!(
+                    "Response exceeded the {} byte limit; the domain has too many certificates",
+                    self.max_response_bytes
+                ))
+            }
+            other => other,
+        }
+    }
+}
+
+// Nomes de um certificado que pertencem ao domínio, com a marcação de "derivado".
+// Uma entrada "*.internal.example.com" revela internal.example.com, que entra como derivado
+fn certificate_names(name_value: &str, domain: &str, include_wildcards: bool) -> Vec<(String, bool)> {
+    let suffix = format!(".{}", domain);
+    let mut names = Vec::new();
+    for line in name_value.lines() {
+        let name = line.trim().to_lowercase();
+        let (base, derived) = match name.strip_prefix("*.") {
+            Some(base) => (base.to_string(), true),
+            None => (name.clone(), false),
+        };
+
+        if base.contains('*') || !base.ends_with(&suffix) {
+            continue;
+        }
+        if derived && include_wildcards {
+            names.push((name, false));
+        }
+        names.push((base, derived));
+    }
+    names
+}
+
+fn html_error_message(text: &str) -> Option<&'static str> {
+    let head = text.trim_start();
+    if !(head.starts_with("<!DOCTYPE") || head.starts_with("<html") || head.starts_with("<HTML")) {
+        return None;
+    }
+    if head.contains("502 Bad Gateway") {
+        Some("crt.sh returned 502 Bad Gateway")
+    } else if head.contains("504 Gateway Time") {
+        Some("crt.sh returned 504 Gateway Timeout")
+    } else {
+        Some("Received HTML response instead of JSON")
     }
 }
 
@@ -40,6 +124,7 @@ impl Source for CrtShSource {
             name: self.name().to_string(),
             is_default: true,
             needs_key: false,
+            category: SourceCategory::Cert,
         }
     }
 
@@ -56,18 +141,15 @@ impl Source for CrtShSource {
         let request_builder = session.client
             .get(&url)
             .header("Accept", "application/json")
-            .timeout(std::time::Duration::from_secs(30));
+            .timeout(self.timeout);
         
         match session.send_request_with_retry(request_builder, &self.name).await {
             Ok(response) => {
-                let text = response.text().await
-                    .map_err(|e| RustFinderError::NetworkError(e.to_string()))?;
-
-                if text.trim_start().starts_with("<!DOCTYPE") || text.trim_start().starts_with("<html") {
-                    return Err(RustFinderError::SourceError {
-                        source_name: self.name.to_string(),
-                        message: "Received HTML response instead of JSON".to_string(),
-                    });
+                let text = session.read_text_limited(response, self.max_response_bytes).await
+                    .map_err(|e| self.map_error(e))?;
+
+                if let Some(message) = html_error_message(&text) {
+                    return Err(self.source_error(message.to_string()));
                 }
 
                 if text.trim().is_empty() || text.trim() == "[]" {
@@ -77,32 +159,78 @@ impl Source for CrtShSource {
                 let crt_results: Vec<CrtShResponse> = serde_json::from_str(&text)
                     .map_err(|e| RustFinderError::JsonParseError(e.to_string(), text))?;
 
-                let mut found_subdomains = HashSet::new();
-                let mut results = Vec::new();
+                let mut found_subdomains: HashMap<String, usize> = HashMap::new();
+                let mut results: Vec<SubdomainResult> = Vec::new();
                 
                 for crt_result in crt_results {
 
-                    for line in crt_result.name_value.lines() {
-                        let subdomain = line.trim().to_lowercase();
-                        
-                        if !subdomain.starts_with('*') && 
-                           subdomain.ends_with(domain) && 
-                           subdomain != domain &&
-                           found_subdomains.insert(subdomain.clone()) {
-                            results.push(SubdomainResult {
-                                subdomain,
-                                source: self.name.to_string(),
-                                resolved: false,
-                                ip_addresses: Vec::new(),
-                            });
+                    for (subdomain, derived) in certificate_names(&crt_result.name_value, domain, self.include_wildcards) {
+                        if let Some(&idx) = found_subdomains.get(&subdomain) {
+                            // Visto diretamente em algum certificado deixa de ser derivado
+                            results[idx].derived &= derived;
+                            // Mantém a data mais antiga entre os certificados
+                            if let Some(not_before) = &crt_result.not_before {
+                                let is_earlier = match &results[idx].first_seen {
+                                    Some(current) => not_before < current,
+                                    None => true,
+                                };
+                                if is_earlier {
+                                    results[idx].first_seen = Some(not_before.clone());
+                                }
+                            }
+                            continue;
                         }
+
+                        found_subdomains.insert(subdomain.clone(), results.len());
+                        results.push(SubdomainResult {
+                            subdomain,
+                            source: self.name.to_string(),
+                            first_seen: crt_result.not_before.clone(),
+                            derived,
+                            ..Default::default()
+                        });
                     }
                 }
 
                 log::info!("[{}] Encontrados {} subdomínios únicos", self.name, results.len());
                 Ok(results)
             }
-            Err(e) => Err(e),
+            Err(e) => Err(self.map_error(e)),
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_certificate_names_wildcards() {
+        let names = certificate_names(
+            "*.internal.example.com\nwww.example.com\n*.example.com\nwww.notexample.com",
+            "example.com",
+            false,
+        );
+        assert_eq!(names, vec![
+            ("internal.example.com".to_string(), true),
+            ("www.example.com".to_string(), false),
+        ]);
+
+        let names = certificate_names("*.internal.example.com", "example.com", true);
+        assert_eq!(names, vec![
+            ("*.internal.example.com".to_string(), false),
+            ("internal.example.com".to_string(), true),
+        ]);
+    }
+
+    #[test]
+    fn test_html_error_message() {
+        let bad_gateway = "<html>\r\n<head><title>502 Bad Gateway</title></head>\r\n</html>";
+        assert_eq!(html_error_message(bad_gateway), Some("crt.sh returned 502 Bad Gateway"));
+
+        let timeout = "<!DOCTYPE html><html><title>504 Gateway Time-out</title></html>";
+        assert_eq!(html_error_message(timeout), Some("crt.sh returned 504 Gateway Timeout"));
+
+        assert_eq!(html_error_message("[{\"id\": 1}]"), None);
+    }
 }
\ No newline at end of file
@@ -1,6 +1,6 @@
 // src/sources/chaos.rs
-use crate::sources::Source;
-use crate::types::{RustFinderError, SourceInfo, SubdomainResult};
+use crate::sources::{with_key_rotation, Source};
+use crate::types::{RustFinderError, SourceCategory, SourceInfo, SubdomainResult};
 use crate::session::Session;
 use async_trait::async_trait;
 use log::{info, warn};
@@ -38,44 +38,12 @@ impl ChaosSource {
         self
     }
 
-    fn get_random_api_key(&self) -> Option<&String> {
-        if self.api_keys.is_empty() {
-            None
-        } else {
-            use rand::seq::SliceRandom;
-            let mut rng = rand::thread_rng();
-            self.api_keys.choose(&mut rng)
-        }
-    }
-}
-
-#[async_trait]
-impl Source for ChaosSource {
-    fn name(&self) -> &str {
-        &self.name
-    }
-
-    fn info(&self) -> SourceInfo {
-        SourceInfo {
-            name: self.name().to_string(),
-            needs_key: true,
-            is_default: true,
-        }
-    }
-
-    fn clone_source(&self) -> Box<dyn Source> {
-        Box::new(self.clone())
-    }
-
-    async fn enumerate(&self, domain: &str, session: &Session) -> Result<Vec<SubdomainResult>, RustFinderError> {
-        let api_key = match self.get_random_api_key() {
-            Some(key) => key,
-            None => {
-                warn!("[{}] Pulando fonte: Nenhuma API key configurada.", self.name);
-                return Ok(Vec::new());
-            }
-        };
-
+    async fn enumerate_with_key(
+        &self,
+        domain: &str,
+        api_key: &str,
+        session: &Session,
+    ) -> Result<Vec<SubdomainResult>, RustFinderError> {
         session.check_rate_limit(&self.name).await?;
 
         let url = format!("https://dns.projectdiscovery.io/dns/{}/subdomains", domain);
@@ -98,9 +66,9 @@ impl Source for ChaosSource {
                     });
                 }
 
-                let chaos_response: ChaosResponse = response.json().await.map_err(|e| {
-                    RustFinderError::JsonParseError(e.to_string(), "Failed to parse Chaos response".to_string())
-                })?;
+                let text = session.read_text(response).await?;
+                let chaos_response: ChaosResponse = serde_json::from_str(&text)
+                    .map_err(|e| RustFinderError::JsonParseError(e.to_string(), text))?;
 
                 let mut found_subdomains = HashSet::new();
                 let mut results = Vec::new();
@@ -116,8 +84,7 @@ impl Source for ChaosSource {
                         results.push(SubdomainResult {
                             subdomain: full_subdomain,
                             source: self.name.to_string(),
-                            resolved: false,
-                            ip_addresses: Vec::new(),
+                            ..Default::default()
                         });
                     }
                 }
@@ -128,4 +95,45 @@ impl Source for ChaosSource {
             Err(e) => Err(e),
         }
     }
+}
+
+#[async_trait]
+impl Source for ChaosSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn info(&self) -> SourceInfo {
+        SourceInfo {
+            name: self.name().to_string(),
+            needs_key: true,
+            is_default: true,
+            category: SourceCategory::PassiveDns,
+        }
+    }
+
+    fn clone_source(&self) -> Box<dyn Source> {
+        Box::new(self.clone())
+    }
+
+    async fn validate_key(&self, key: &str, session: &Session) -> Result<(), RustFinderError> {
+        let request_builder = session.client
+            .get("https://dns.projectdiscovery.io/dns/projectdiscovery.io")
+            .header("Authorization", key)
+            .header("Accept", "application/json");
+        session.send_request_with_retry(request_builder, &self.name).await?;
+        Ok(())
+    }
+
+    async fn enumerate(&self, domain: &str, session: &Session) -> Result<Vec<SubdomainResult>, RustFinderError> {
+        if self.api_keys.is_empty() {
+            warn!("[{}] Pulando fonte: Nenhuma API key configurada.", self.name);
+            return Ok(Vec::new());
+        }
+
+        with_key_rotation(&self.name, &self.api_keys, |api_key| {
+            self.enumerate_with_key(domain, api_key, session)
+        })
+        .await
+    }
 }
\ No newline at end of file
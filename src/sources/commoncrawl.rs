@@ -0,0 +1,138 @@
+// src/sources/commoncrawl.rs
+use crate::session::Session;
+use crate::sources::{collect_stream, Source};
+use crate::types::{RustFinderError, SourceCategory, SourceInfo, SubdomainResult};
+use crate::utils;
+use async_trait::async_trait;
+use log::{debug, info};
+use serde::Deserialize;
+use std::collections::HashSet;
+use tokio::sync::mpsc::Sender;
+
+const COLLINFO_URL: &str = "https://index.commoncrawl.org/collinfo.json";
+
+#[derive(Debug, Deserialize)]
+struct CollectionInfo {
+    id: String,
+    #[serde(rename = "cdx-api", default)]
+    cdx_api: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IndexRecord {
+    url: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CommonCrawlSource {
+    name: String,
+}
+
+impl Default for CommonCrawlSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommonCrawlSource {
+    pub fn new() -> Self {
+        Self { name: "commoncrawl".to_string() }
+    }
+
+    async fn latest_index_url(&self, session: &Session) -> Result<String, RustFinderError> {
+        let collections: Vec<CollectionInfo> = session.get_json(COLLINFO_URL, &self.name).await?;
+
+        // O collinfo.json lista os índices do mais recente para o mais antigo
+        let latest = collections.into_iter().next().ok_or_else(|| RustFinderError::SourceError {
+            source_name: self.name.to_string(),
+            message: "collinfo.json não retornou nenhum índice".to_string(),
+        })?;
+
+        Ok(latest
+            .cdx_api
+            .unwrap_or_else(|| format!("https://index.commoncrawl.org/{}-index", latest.id)))
+    }
+
+    // Cada linha da resposta é um registro JSON independente
+    fn parse_record(&self, line: &str, suffix: &str, found_subdomains: &mut HashSet<String>) -> Option<SubdomainResult> {
+        let line = line.trim();
+        if line.is_empty() {
+            return None;
+        }
+        let record_url = serde_json::from_str::<IndexRecord>(line).ok()?.url?;
+        let subdomain = utils::extract_domain_from_url(&record_url).ok()?.to_lowercase();
+        if !subdomain.ends_with(suffix) || !found_subdomains.insert(subdomain.clone()) {
+            return None;
+        }
+        Some(SubdomainResult {
+            subdomain,
+            source: self.name.to_string(),
+            ..Default::default()
+        })
+    }
+}
+
+#[async_trait]
+impl Source for CommonCrawlSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn info(&self) -> SourceInfo {
+        SourceInfo {
+            name: self.name().to_string(),
+            needs_key: false,
+            is_default: false,
+            category: SourceCategory::Web,
+        }
+    }
+
+    fn clone_source(&self) -> Box<dyn Source> {
+        Box::new(self.clone())
+    }
+
+    async fn enumerate(&self, domain: &str, session: &Session) -> Result<Vec<SubdomainResult>, RustFinderError> {
+        collect_stream(|tx| self.enumerate_stream(domain, session, tx)).await
+    }
+
+    // O índice pode devolver centenas de MB; as linhas são processadas conforme chegam
+    async fn enumerate_stream(
+        &self,
+        domain: &str,
+        session: &Session,
+        tx: Sender<SubdomainResult>,
+    ) -> Result<(), RustFinderError> {
+        session.check_rate_limit(&self.name).await?;
+        let index_url = self.latest_index_url(session).await?;
+        debug!("[{}] Usando índice {}", self.name, index_url);
+
+        session.check_rate_limit(&self.name).await?;
+        let url = format!("{}?url=*.{}&output=json&fl=url", index_url, domain);
+
+        let response = match session.get(&url, &self.name).await {
+            Ok(response) => response,
+            // O índice responde 404 quando não há capturas para o domínio
+            Err(RustFinderError::NetworkError(message)) if message.starts_with("HTTP error: 404") => {
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+
+        let suffix = format!(".{}", domain);
+        let mut found_subdomains = HashSet::new();
+        let mut lines = session.read_lines(response);
+        let mut sent = 0;
+
+        while let Some(line) = lines.next_line().await? {
+            if let Some(result) = self.parse_record(&line, &suffix, &mut found_subdomains) {
+                if tx.send(result).await.is_err() {
+                    return Ok(());
+                }
+                sent += 1;
+            }
+        }
+
+        info!("[{}] Encontrados {} subdomínios únicos", self.name, sent);
+        Ok(())
+    }
+}
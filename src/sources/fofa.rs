@@ -0,0 +1,228 @@
+// src/sources/fofa.rs
+use crate::session::Session;
+use crate::sources::{with_key_rotation, Source};
+use crate::types::{RustFinderError, SourceCategory, SourceInfo, SubdomainResult};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use log::{info, warn};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+const PAGE_SIZE: u32 = 10000;
+
+#[derive(Debug, Deserialize)]
+struct FofaResponse {
+    #[serde(default)]
+    error: bool,
+    #[serde(default)]
+    errmsg: String,
+    #[serde(default)]
+    results: Vec<FofaRow>,
+}
+
+// Com um único campo (fields=host) cada linha vem como string; com vários, como lista
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum FofaRow {
+    Single(String),
+    Fields(Vec<String>),
+}
+
+impl FofaRow {
+    fn host(&self) -> Option<&str> {
+        match self {
+            FofaRow::Single(host) => Some(host),
+            FofaRow::Fields(fields) => fields.first().map(String::as_str),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FofaSource {
+    name: String,
+    api_keys: Vec<String>,
+}
+
+impl Default for FofaSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FofaSource {
+    pub fn new() -> Self {
+        Self {
+            name: "fofa".to_string(),
+            api_keys: Vec::new(),
+        }
+    }
+
+    pub fn with_api_keys(mut self, keys: Vec<String>) -> Self {
+        self.api_keys = keys;
+        self
+    }
+
+    // A chave é configurada como "email:key"
+    fn split_key<'a>(&self, api_key: &'a str) -> Result<(&'a str, &'a str), RustFinderError> {
+        match api_key.split_once(':') {
+            Some((email, key)) if !email.is_empty() && !key.is_empty() => Ok((email, key)),
+            _ => Err(RustFinderError::ApiKeyError(format!(
+                "{}: a chave deve estar no formato email:key",
+                self.name
+            ))),
+        }
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        api_key: &str,
+        size: u32,
+        session: &Session,
+    ) -> Result<FofaResponse, RustFinderError> {
+        let (email, key) = self.split_key(api_key)?;
+        session.check_rate_limit(&self.name).await?;
+
+        let url = format!(
+            "https://fofa.info/api/v1/search/all?email={}&key={}&qbase64={}&size={}&fields=host",
+            urlencoding::encode(email),
+            urlencoding::encode(key),
+            urlencoding::encode(&STANDARD.encode(query)),
+            size
+        );
+
+        let response = session.get(&url, &self.name).await
+            .map_err(|e| self.map_error(e))?;
+        let text = session.read_text(response).await?;
+        let fofa_response: FofaResponse = serde_json::from_str(&text)
+            .map_err(|e| RustFinderError::JsonParseError(e.to_string(), text))?;
+
+        if fofa_response.error {
+            return Err(RustFinderError::SourceError {
+                source_name: self.name.to_string(),
+                message: fofa_response.errmsg,
+            });
+        }
+
+        Ok(fofa_response)
+    }
+
+    async fn enumerate_with_key(
+        &self,
+        domain: &str,
+        api_key: &str,
+        session: &Session,
+    ) -> Result<Vec<SubdomainResult>, RustFinderError> {
+        let query = format!("domain=\"{}\"", domain);
+        let fofa_response = self.search(&query, api_key, PAGE_SIZE, session).await?;
+
+        let suffix = format!(".{}", domain);
+        let mut found_subdomains = HashSet::new();
+        let mut results = Vec::new();
+
+        for row in fofa_response.results {
+            let Some(host) = row.host() else {
+                continue;
+            };
+            if let Some(subdomain) = extract_host(host) {
+                if subdomain.ends_with(&suffix) && found_subdomains.insert(subdomain.clone()) {
+                    results.push(SubdomainResult {
+                        subdomain,
+                        source: self.name.to_string(),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+
+        info!("[{}] Encontrados {} subdomínios únicos", self.name, results.len());
+        Ok(results)
+    }
+
+    fn map_error(&self, error: RustFinderError) -> RustFinderError {
+        match error {
+            RustFinderError::NetworkError(message)
+                if message.starts_with("HTTP error: 401") || message.starts_with("HTTP error: 403") =>
+            {
+                RustFinderError::ApiKeyError(format!("{}: {}", self.name, message))
+            }
+            RustFinderError::RateLimitExceeded { .. } => RustFinderError::RateLimitError(self.name.to_string()),
+            other => other,
+        }
+    }
+}
+
+// O campo host pode vir como "https://a.example.com:8443" ou "a.example.com:80"
+fn extract_host(host: &str) -> Option<String> {
+    let host = host.trim();
+    let host = host.split_once("://").map(|(_, rest)| rest).unwrap_or(host);
+    let host = host.split(['/', ':']).next()?;
+    let host = host.trim_end_matches('.').to_lowercase();
+    if host.is_empty() {
+        None
+    } else {
+        Some(host)
+    }
+}
+
+#[async_trait]
+impl Source for FofaSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn info(&self) -> SourceInfo {
+        SourceInfo {
+            name: self.name().to_string(),
+            needs_key: true,
+            is_default: false,
+            category: SourceCategory::Search,
+        }
+    }
+
+    fn clone_source(&self) -> Box<dyn Source> {
+        Box::new(self.clone())
+    }
+
+    async fn validate_key(&self, key: &str, session: &Session) -> Result<(), RustFinderError> {
+        self.search("domain=\"example.com\"", key, 1, session).await?;
+        Ok(())
+    }
+
+    async fn enumerate(&self, domain: &str, session: &Session) -> Result<Vec<SubdomainResult>, RustFinderError> {
+        if self.api_keys.is_empty() {
+            warn!("[{}] Pulando fonte: Nenhuma API key configurada.", self.name);
+            return Ok(Vec::new());
+        }
+
+        with_key_rotation(&self.name, &self.api_keys, |api_key| {
+            self.enumerate_with_key(domain, api_key, session)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_host() {
+        assert_eq!(extract_host("https://a.example.com:8443"), Some("a.example.com".to_string()));
+        assert_eq!(extract_host("B.Example.com:80"), Some("b.example.com".to_string()));
+        assert_eq!(extract_host("c.example.com"), Some("c.example.com".to_string()));
+        assert_eq!(extract_host(""), None);
+    }
+
+    #[test]
+    fn test_parse_single_field_response() {
+        let text = r#"{"error":false,"consumed_fpoint":0,"required_fpoints":0,"size":3,"page":1,"mode":"extended","query":"domain=\"example.com\"","results":["https://a.example.com","b.example.com:8080","example.com"]}"#;
+        let response: FofaResponse = serde_json::from_str(text).unwrap();
+        let hosts: Vec<&str> = response.results.iter().filter_map(FofaRow::host).collect();
+        assert_eq!(hosts, vec!["https://a.example.com", "b.example.com:8080", "example.com"]);
+
+        let text = r#"{"error":false,"results":[["a.example.com","192.0.2.1"]]}"#;
+        let response: FofaResponse = serde_json::from_str(text).unwrap();
+        assert_eq!(response.results[0].host(), Some("a.example.com"));
+    }
+}
@@ -0,0 +1,132 @@
+// src/sources/fullhunt.rs
+use crate::session::Session;
+use crate::sources::{with_key_rotation, Source};
+use crate::types::{RustFinderError, SourceCategory, SourceInfo, SubdomainResult};
+use async_trait::async_trait;
+use log::{info, warn};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+#[derive(Debug, Deserialize)]
+struct FullHuntResponse {
+    #[serde(default)]
+    hosts: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FullHuntSource {
+    name: String,
+    api_keys: Vec<String>,
+}
+
+impl Default for FullHuntSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FullHuntSource {
+    pub fn new() -> Self {
+        Self {
+            name: "fullhunt".to_string(),
+            api_keys: Vec::new(),
+        }
+    }
+
+    pub fn with_api_keys(mut self, keys: Vec<String>) -> Self {
+        self.api_keys = keys;
+        self
+    }
+
+    async fn enumerate_with_key(
+        &self,
+        domain: &str,
+        api_key: &str,
+        session: &Session,
+    ) -> Result<Vec<SubdomainResult>, RustFinderError> {
+        session.check_rate_limit(&self.name).await?;
+
+        let url = format!("https://fullhunt.io/api/v1/domain/{}/subdomains", domain);
+        let request_builder = session.client
+            .get(&url)
+            .header("X-API-KEY", api_key)
+            .header("Accept", "application/json");
+
+        let response = session.send_request_with_retry(request_builder, &self.name).await
+            .map_err(|e| self.map_error(e))?;
+        let text = session.read_text(response).await?;
+        let fullhunt_response: FullHuntResponse = serde_json::from_str(&text)
+            .map_err(|e| RustFinderError::JsonParseError(e.to_string(), text))?;
+
+        let suffix = format!(".{}", domain);
+        let mut found_subdomains = HashSet::new();
+        let mut results = Vec::new();
+
+        for host in fullhunt_response.hosts {
+            let subdomain = host.trim().trim_end_matches('.').to_lowercase();
+            if subdomain.ends_with(&suffix) && found_subdomains.insert(subdomain.clone()) {
+                results.push(SubdomainResult {
+                    subdomain,
+                    source: self.name.to_string(),
+                    ..Default::default()
+                });
+            }
+        }
+
+        info!("[{}] Encontrados {} subdomínios únicos", self.name, results.len());
+        Ok(results)
+    }
+
+    fn map_error(&self, error: RustFinderError) -> RustFinderError {
+        match error {
+            RustFinderError::NetworkError(message)
+                if message.starts_with("HTTP error: 401") || message.starts_with("HTTP error: 403") =>
+            {
+                RustFinderError::ApiKeyError(format!("{}: {}", self.name, message))
+            }
+            RustFinderError::RateLimitExceeded { .. } => RustFinderError::RateLimitError(self.name.to_string()),
+            other => other,
+        }
+    }
+}
+
+#[async_trait]
+impl Source for FullHuntSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn info(&self) -> SourceInfo {
+        SourceInfo {
+            name: self.name().to_string(),
+            needs_key: true,
+            is_default: false,
+            category: SourceCategory::Search,
+        }
+    }
+
+    fn clone_source(&self) -> Box<dyn Source> {
+        Box::new(self.clone())
+    }
+
+    async fn validate_key(&self, key: &str, session: &Session) -> Result<(), RustFinderError> {
+        let request_builder = session.client
+            .get("https://fullhunt.io/api/v1/auth/status")
+            .header("X-API-KEY", key);
+        session.send_request_with_retry(request_builder, &self.name).await
+            .map_err(|e| self.map_error(e))?;
+        Ok(())
+    }
+
+    async fn enumerate(&self, domain: &str, session: &Session) -> Result<Vec<SubdomainResult>, RustFinderError> {
+        if self.api_keys.is_empty() {
+            warn!("[{}] Pulando fonte: Nenhuma API key configurada.", self.name);
+            return Ok(Vec::new());
+        }
+
+        with_key_rotation(&self.name, &self.api_keys, |api_key| {
+            self.enumerate_with_key(domain, api_key, session)
+        })
+        .await
+    }
+}
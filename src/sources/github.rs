@@ -1,6 +1,6 @@
 // src/sources/github.rs
-use crate::sources::Source;
-use crate::types::{RustFinderError, SourceInfo, SubdomainResult};
+use crate::sources::{with_key_rotation, Source};
+use crate::types::{RustFinderError, SourceCategory, SourceInfo, SubdomainResult};
 use crate::session::Session;
 use async_trait::async_trait;
 use log::{info, warn};
@@ -52,70 +52,12 @@ impl GitHubSource {
         self
     }
 
-    fn get_random_api_key(&self) -> Option<&String> {
-        if self.api_keys.is_empty() {
-            None
-        } else {
-            use rand::seq::SliceRandom;
-            let mut rng = rand::thread_rng();
-            self.api_keys.choose(&mut rng)
-        }
-    }
-
-    fn extract_subdomains(&self, text: &str, domain: &str) -> Vec<String> {
-        let mut subdomains = HashSet::new();
-        
-        let pattern = format!(
-            r"(?i)(?:^|[^a-zA-Z0-9.-])([a-zA-Z0-9](?:[a-zA-Z0-9-]{{0,61}}[a-zA-Z0-9])?(?:\.[a-zA-Z0-9](?:[a-zA-Z0-9-]{{0,61}}[a-zA-Z0-9])?)*\.{})",
-            regex::escape(domain)
-        );
-        
-        if let Ok(re) = Regex::new(&pattern) {
-            for cap in re.captures_iter(text) {
-                if let Some(subdomain) = cap.get(1) {
-                    let subdomain_str = subdomain.as_str().to_lowercase();
-                    // Validate subdomain
-                    if subdomain_str != domain && 
-                       !subdomain_str.contains("..") &&
-                       !subdomain_str.starts_with('.') &&
-                       !subdomain_str.ends_with('.') {
-                        subdomains.insert(subdomain_str);
-                    }
-                }
-            }
-        }
-        
-        subdomains.into_iter().collect()
-    }
-}
-
-#[async_trait]
-impl Source for GitHubSource {
-    fn name(&self) -> &str {
-        &self.name
-    }
-
-    fn info(&self) -> SourceInfo {
-        SourceInfo {
-            name: self.name().to_string(),
-            needs_key: true,
-            is_default: true,
-        }
-    }
-
-    fn clone_source(&self) -> Box<dyn Source> {
-        Box::new(self.clone())
-    }
-
-    async fn enumerate(&self, domain: &str, session: &Session) -> Result<Vec<SubdomainResult>, RustFinderError> {
-        let api_key = match self.get_random_api_key() {
-            Some(key) => key,
-            None => {
-                warn!("[{}] Pulando fonte: Nenhuma API key configurada.", self.name);
-                return Ok(Vec::new());
-            }
-        };
-
+    async fn enumerate_with_key(
+        &self,
+        domain: &str,
+        api_key: &str,
+        session: &Session,
+    ) -> Result<Vec<SubdomainResult>, RustFinderError> {
         session.check_rate_limit(&self.name).await?;
 
         let mut results = Vec::new();
@@ -135,18 +77,7 @@ impl Source for GitHubSource {
 
         match session.send_request_with_retry(request_builder, &self.name).await {
             Ok(response) => {
-                // Check rate limit headers
-                if let Some(remaining) = response.headers().get("x-ratelimit-remaining") {
-                    if let Ok(remaining_str) = remaining.to_str() {
-                        if let Ok(remaining_count) = remaining_str.parse::<i32>() {
-                            if remaining_count < 10 {
-                                warn!("[{}] GitHub API rate limit baixo: {} requisições restantes", 
-                                      self.name, remaining_count);
-                            }
-                     }
-                    }
-                }
-
+                // Os cabeçalhos x-ratelimit-* já são tratados pela Session (espera pelo reset)
                 let status = response.status();
                 
                 if !status.is_success() {
@@ -163,8 +94,7 @@ impl Source for GitHubSource {
                     });
                 }
 
-                let text = response.text().await
-                    .map_err(|e| RustFinderError::NetworkError(e.to_string()))?;
+                let text = session.read_text(response).await?;
 
                 let github_response: GitHubSearchResponse = serde_json::from_str(&text)
                     .map_err(|e| RustFinderError::JsonParseError(e.to_string(), text))?;
@@ -178,8 +108,8 @@ impl Source for GitHubSource {
                                     results.push(SubdomainResult {
                                         subdomain,
                                         source: self.name.to_string(),
-                                        resolved: false,
-                                        ip_addresses: Vec::new(),
+                                        reference: Some(item.html_url.clone()),
+                                        ..Default::default()
                                     });
                                 }
                             }
@@ -194,4 +124,71 @@ impl Source for GitHubSource {
             Err(e) => Err(e),
         }
     }
+
+    fn extract_subdomains(&self, text: &str, domain: &str) -> Vec<String> {
+        let mut subdomains = HashSet::new();
+        
+        let pattern = format!(
+            r"(?i)(?:^|[^a-zA-Z0-9.-])([a-zA-Z0-9](?:[a-zA-Z0-9-]{{0,61}}[a-zA-Z0-9])?(?:\.[a-zA-Z0-9](?:[a-zA-Z0-9-]{{0,61}}[a-zA-Z0-9])?)*\.{})",
+            regex::escape(domain)
+        );
+        
+        if let Ok(re) = Regex::new(&pattern) {
+            for cap in re.captures_iter(text) {
+                if let Some(subdomain) = cap.get(1) {
+                    let subdomain_str = subdomain.as_str().to_lowercase();
+                    // Validate subdomain
+                    if subdomain_str != domain && 
+                       !subdomain_str.contains("..") &&
+                       !subdomain_str.starts_with('.') &&
+                       !subdomain_str.ends_with('.') {
+                        subdomains.insert(subdomain_str);
+                    }
+                }
+            }
+        }
+        
+        subdomains.into_iter().collect()
+    }
+}
+
+#[async_trait]
+impl Source for GitHubSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn info(&self) -> SourceInfo {
+        SourceInfo {
+            name: self.name().to_string(),
+            needs_key: true,
+            is_default: true,
+            category: SourceCategory::Search,
+        }
+    }
+
+    fn clone_source(&self) -> Box<dyn Source> {
+        Box::new(self.clone())
+    }
+
+    async fn validate_key(&self, key: &str, session: &Session) -> Result<(), RustFinderError> {
+        let request_builder = session.client
+            .get("https://api.github.com/rate_limit")
+            .header("Authorization", format!("Bearer {}", key))
+            .header("X-GitHub-Api-Version", "2022-11-28");
+        session.send_request_with_retry(request_builder, &self.name).await?;
+        Ok(())
+    }
+
+    async fn enumerate(&self, domain: &str, session: &Session) -> Result<Vec<SubdomainResult>, RustFinderError> {
+        if self.api_keys.is_empty() {
+            warn!("[{}] Pulando fonte: Nenhuma API key configurada.", self.name);
+            return Ok(Vec::new());
+        }
+
+        with_key_rotation(&self.name, &self.api_keys, |api_key| {
+            self.enumerate_with_key(domain, api_key, session)
+        })
+        .await
+    }
 }
\ No newline at end of file
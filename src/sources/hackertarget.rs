@@ -1,6 +1,6 @@
 // src/sources/hackertarget.rs
 use crate::session::Session;
-use crate::types::{RustFinderError, SourceInfo, SubdomainResult};
+use crate::types::{RustFinderError, SourceCategory, SourceInfo, SubdomainResult};
 use async_trait::async_trait;
 use crate::sources::Source;
 
@@ -19,6 +19,24 @@ impl HackerTargetSource {
     pub fn new() -> Self {
         Self { name: "hackertarget".to_string() }
     }
+
+    fn check_error_body(text: &str) -> Result<(), RustFinderError> {
+        let body = text.trim();
+        let lower = body.to_lowercase();
+
+        if lower.starts_with("api count exceeded") {
+            return Err(RustFinderError::RateLimitError("hackertarget".to_string()));
+        }
+
+        if lower.starts_with("error") {
+            return Err(RustFinderError::SourceError {
+                source_name: "hackertarget".to_string(),
+                message: body.to_string(),
+            });
+        }
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -32,6 +50,7 @@ impl crate::sources::Source for HackerTargetSource {
             name: self.name().to_string(),
             is_default: true,
             needs_key: false,
+            category: SourceCategory::PassiveDns,
         }
     }
 
@@ -44,8 +63,8 @@ impl crate::sources::Source for HackerTargetSource {
         
         match session.get(&url, &self.name).await {
             Ok(response) => {
-                let text = response.text().await
-                    .map_err(|e| RustFinderError::NetworkError(e.to_string()))?;
+                let text = session.read_text(response).await?;
+                Self::check_error_body(&text)?;
                 let mut results = Vec::new();
 
                 for line in text.lines() {
@@ -70,6 +89,7 @@ impl crate::sources::Source for HackerTargetSource {
                                 source: self.name.to_string(),
                                 resolved: !ip_addresses.is_empty(),
                                 ip_addresses,
+                                ..Default::default()
                             });
                         }
                     }
@@ -82,4 +102,21 @@ impl crate::sources::Source for HackerTargetSource {
             }),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_error_body() {
+        let exceeded = HackerTargetSource::check_error_body("API count exceeded - Increase Quota with Membership");
+        assert!(matches!(exceeded, Err(RustFinderError::RateLimitError(_))));
+
+        let error = HackerTargetSource::check_error_body("error check your search parameter\n");
+        assert!(matches!(error, Err(RustFinderError::SourceError { message, .. })
+            if message == "error check your search parameter"));
+
+        assert!(HackerTargetSource::check_error_body("www.example.com,192.0.2.1\n").is_ok());
+    }
+}
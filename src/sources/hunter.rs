@@ -0,0 +1,221 @@
+// src/sources/hunter.rs
+use crate::session::Session;
+use crate::sources::{with_key_rotation, Source};
+use crate::types::{RustFinderError, SourceCategory, SourceInfo, SubdomainResult, DEFAULT_MAX_PAGES};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::URL_SAFE, Engine as _};
+use log::{info, warn};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+const PAGE_SIZE: u32 = 100;
+// A API só aceita consultas dentro de uma janela de até um ano
+const SEARCH_WINDOW_DAYS: i64 = 365;
+
+#[derive(Debug, Deserialize)]
+struct HunterResponse {
+    code: i64,
+    #[serde(default)]
+    message: String,
+    data: Option<HunterData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HunterData {
+    #[serde(default)]
+    list: Vec<HunterItem>,
+    #[serde(default)]
+    total: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct HunterItem {
+    #[serde(default)]
+    domain: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct HunterSource {
+    name: String,
+    api_keys: Vec<String>,
+    max_pages: u32,
+}
+
+impl Default for HunterSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HunterSource {
+    pub fn new() -> Self {
+        Self {
+            name: "hunter".to_string(),
+            api_keys: Vec::new(),
+            max_pages: DEFAULT_MAX_PAGES,
+        }
+    }
+
+    pub fn with_api_keys(mut self, keys: Vec<String>) -> Self {
+        self.api_keys = keys;
+        self
+    }
+
+    pub fn with_max_pages(mut self, max_pages: u32) -> Self {
+        self.max_pages = max_pages.max(1);
+        self
+    }
+
+    async fn fetch_page(
+        &self,
+        query: &str,
+        api_key: &str,
+        page: u32,
+        page_size: u32,
+        session: &Session,
+    ) -> Result<HunterData, RustFinderError> {
+        session.check_rate_limit(&self.name).await?;
+
+        let end = chrono::Utc::now().date_naive();
+        let start = end - chrono::Duration::days(SEARCH_WINDOW_DAYS);
+        let url = format!(
+            "https://api.hunter.how/search?api-key={}&query={}&page={}&page_size={}&start_time={}&end_time={}",
+            urlencoding::encode(api_key),
+            urlencoding::encode(&URL_SAFE.encode(query)),
+            page,
+            page_size,
+            start.format("%Y-%m-%d"),
+            end.format("%Y-%m-%d")
+        );
+
+        let response = session.get(&url, &self.name).await
+            .map_err(|e| self.map_error(e))?;
+        let text = session.read_text(response).await?;
+        let hunter_response: HunterResponse = serde_json::from_str(&text)
+            .map_err(|e| RustFinderError::JsonParseError(e.to_string(), text))?;
+
+        // Erros vêm com HTTP 200 e o código real no corpo
+        match hunter_response.code {
+            200 => Ok(hunter_response.data.unwrap_or(HunterData { list: Vec::new(), total: 0 })),
+            401 | 403 => Err(RustFinderError::ApiKeyError(format!(
+                "{}: {}",
+                self.name, hunter_response.message
+            ))),
+            429 => Err(RustFinderError::RateLimitError(self.name.to_string())),
+            _ if is_quota_message(&hunter_response.message) => {
+                Err(RustFinderError::RateLimitError(self.name.to_string()))
+            }
+            code => Err(RustFinderError::SourceError {
+                source_name: self.name.to_string(),
+                message: format!("código {}: {}", code, hunter_response.message),
+            }),
+        }
+    }
+
+    async fn enumerate_with_key(
+        &self,
+        domain: &str,
+        api_key: &str,
+        session: &Session,
+    ) -> Result<Vec<SubdomainResult>, RustFinderError> {
+        let query = format!("domain.suffix=\"{}\"", domain);
+        let suffix = format!(".{}", domain);
+        let mut found_subdomains = HashSet::new();
+        let mut results = Vec::new();
+
+        let mut page = 1;
+        loop {
+            let data = self.fetch_page(&query, api_key, page, PAGE_SIZE, session).await?;
+            let received = data.list.len();
+
+            for item in data.list {
+                let subdomain = item.domain.trim().trim_end_matches('.').to_lowercase();
+                if subdomain.ends_with(&suffix) && found_subdomains.insert(subdomain.clone()) {
+                    results.push(SubdomainResult {
+                        subdomain,
+                        source: self.name.to_string(),
+                        ..Default::default()
+                    });
+                }
+            }
+
+            if received < PAGE_SIZE as usize || u64::from(page * PAGE_SIZE) >= data.total {
+                break;
+            }
+            if page >= self.max_pages {
+                warn!("[{}] Limite de {} páginas atingido; resultados podem estar incompletos", self.name, self.max_pages);
+                break;
+            }
+            page += 1;
+        }
+
+        info!("[{}] Encontrados {} subdomínios únicos", self.name, results.len());
+        Ok(results)
+    }
+
+    fn map_error(&self, error: RustFinderError) -> RustFinderError {
+        match error {
+            RustFinderError::NetworkError(message)
+                if message.starts_with("HTTP error: 401") || message.starts_with("HTTP error: 403") =>
+            {
+                RustFinderError::ApiKeyError(format!("{}: {}", self.name, message))
+            }
+            RustFinderError::RateLimitExceeded { .. } => RustFinderError::RateLimitError(self.name.to_string()),
+            other => other,
+        }
+    }
+}
+
+fn is_quota_message(message: &str) -> bool {
+    let message = message.to_lowercase();
+    message.contains("quota") || message.contains("rate limit") || message.contains("too many")
+}
+
+#[async_trait]
+impl Source for HunterSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn info(&self) -> SourceInfo {
+        SourceInfo {
+            name: self.name().to_string(),
+            needs_key: true,
+            is_default: false,
+            category: SourceCategory::Search,
+        }
+    }
+
+    fn clone_source(&self) -> Box<dyn Source> {
+        Box::new(self.clone())
+    }
+
+    async fn validate_key(&self, key: &str, session: &Session) -> Result<(), RustFinderError> {
+        self.fetch_page("domain.suffix=\"example.com\"", key, 1, 10, session).await?;
+        Ok(())
+    }
+
+    async fn enumerate(&self, domain: &str, session: &Session) -> Result<Vec<SubdomainResult>, RustFinderError> {
+        if self.api_keys.is_empty() {
+            warn!("[{}] Pulando fonte: Nenhuma API key configurada.", self.name);
+            return Ok(Vec::new());
+        }
+
+        with_key_rotation(&self.name, &self.api_keys, |api_key| {
+            self.enumerate_with_key(domain, api_key, session)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_quota_message() {
+        assert!(is_quota_message("Insufficient quota"));
+        assert!(is_quota_message("Too many requests"));
+        assert!(!is_quota_message("invalid query"));
+    }
+}
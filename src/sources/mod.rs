@@ -2,6 +2,13 @@
 use crate::types::{Config, RustFinderError, SourceInfo, SubdomainResult};
 use crate::session::Session;
 use async_trait::async_trait;
+use futures::stream::StreamExt;
+use log::warn;
+use rand::seq::SliceRandom;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::mpsc::Sender;
 
 // Importar os módulos dos sources
 mod virustotal;
@@ -13,6 +20,12 @@ mod netlas;
 mod stubs;
 mod certsh;
 mod hackertarget;
+mod rapiddns;
+mod waybackarchive;
+mod fullhunt;
+mod commoncrawl;
+mod fofa;
+mod hunter;
 
 // Re-exportar as implementações específicas
 pub use virustotal::VirusTotalSource;
@@ -23,6 +36,12 @@ pub use github::GitHubSource;
 pub use netlas::NetlasSource;
 pub use certsh::CrtShSource;
 pub use hackertarget::HackerTargetSource;
+pub use rapiddns::RapidDnsSource;
+pub use waybackarchive::WaybackArchiveSource;
+pub use fullhunt::FullHuntSource;
+pub use commoncrawl::CommonCrawlSource;
+pub use fofa::FofaSource;
+pub use hunter::HunterSource;
 
 // Definir a trait Source
 #[async_trait]
@@ -31,6 +50,49 @@ pub trait Source: Send + Sync {
     fn info(&self) -> SourceInfo;
     async fn enumerate(&self, domain: &str, session: &Session) -> Result<Vec<SubdomainResult>, RustFinderError>;
     fn clone_source(&self) -> Box<dyn Source>;
+
+    // Entrega os resultados conforme chegam. O padrão chama enumerate e repassa tudo no fim;
+    // fontes com respostas grandes sobrescrevem para não acumular o corpo inteiro em memória
+    async fn enumerate_stream(
+        &self,
+        domain: &str,
+        session: &Session,
+        tx: Sender<SubdomainResult>,
+    ) -> Result<(), RustFinderError> {
+        for result in self.enumerate(domain, session).await? {
+            // Receptor fechado: ninguém mais quer os resultados
+            if tx.send(result).await.is_err() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    // Fontes cuja API aceita vários domínios numa só consulta retornam true e sobrescrevem enumerate_batch
+    fn supports_batch(&self) -> bool {
+        false
+    }
+
+    // Resultados agrupados por domínio (toda entrada de domains tem uma chave). O padrão consulta um por vez
+    async fn enumerate_batch(
+        &self,
+        domains: &[String],
+        session: &Session,
+    ) -> Result<HashMap<String, Vec<SubdomainResult>>, RustFinderError> {
+        let mut results = HashMap::new();
+        for domain in domains {
+            results.insert(domain.clone(), self.enumerate(domain, session).await?);
+        }
+        Ok(results)
+    }
+
+    // Faz uma requisição autenticada mínima para verificar se a chave é aceita
+    async fn validate_key(&self, _key: &str, _session: &Session) -> Result<(), RustFinderError> {
+        Err(RustFinderError::SourceError {
+            source_name: self.name().to_string(),
+            message: "Validação de chave não suportada".to_string(),
+        })
+    }
 }
 
 // Função para criar sources dinamicamente com configuração
@@ -39,6 +101,7 @@ pub fn create_source(name: &str, config: &Config) -> Option<Box<dyn Source>> {
     let api_keys = config.api_keys.get(name)
         .cloned()
         .unwrap_or_else(Vec::new);
+    let base_url = config.base_urls.get(&name.to_lowercase()).cloned();
 
     match name.to_lowercase().as_str() {
         "virustotal" => {
@@ -46,11 +109,19 @@ pub fn create_source(name: &str, config: &Config) -> Option<Box<dyn Source>> {
             Some(Box::new(source))
         },
         "securitytrails" => {
-            let source = SecurityTrailsSource::new().with_api_keys(api_keys);
+            let source = SecurityTrailsSource::new()
+                .with_api_keys(api_keys)
+                .with_base_url(base_url)
+                .with_max_pages(config.max_pages)
+                .with_rotation(config.key_rotation);
             Some(Box::new(source))
         },
         "shodan" => {
-            let source = ShodanSource::new().with_api_keys(api_keys);
+            let source = ShodanSource::new()
+                .with_api_keys(api_keys)
+                .with_base_url(base_url)
+                .with_max_pages(config.max_pages)
+                .with_rotation(config.key_rotation);
             Some(Box::new(source))
         },
         "chaos" => {
@@ -62,43 +133,252 @@ pub fn create_source(name: &str, config: &Config) -> Option<Box<dyn Source>> {
             Some(Box::new(source))
         },
         "netlas" => {
-            let source = NetlasSource::new().with_api_keys(api_keys);
+            let source = NetlasSource::new().with_api_keys(api_keys).with_base_url(base_url);
             Some(Box::new(source))
         },
         "crtsh" => {
-            let source = CrtShSource::new();
+            let source = CrtShSource::new()
+                .with_limits(config.crtsh_timeout, config.crtsh_max_response_bytes)
+                .with_include_wildcards(config.include_wildcards);
             Some(Box::new(source))
         },
         "hackertarget" => {
             let source = HackerTargetSource::new();
             Some(Box::new(source))
         },
+        "rapiddns" => {
+            let source = RapidDnsSource::new();
+            Some(Box::new(source))
+        },
+        "waybackarchive" => {
+            let source = WaybackArchiveSource::new();
+            Some(Box::new(source))
+        },
+        "fullhunt" => {
+            let source = FullHuntSource::new().with_api_keys(api_keys);
+            Some(Box::new(source))
+        },
+        "commoncrawl" => {
+            let source = CommonCrawlSource::new();
+            Some(Box::new(source))
+        },
+        "fofa" => {
+            let source = FofaSource::new().with_api_keys(api_keys);
+            Some(Box::new(source))
+        },
+        "hunter" => {
+            let source = HunterSource::new()
+                .with_api_keys(api_keys)
+                .with_max_pages(config.max_pages);
+            Some(Box::new(source))
+        },
         _ => None,
     }
 }
 
+// Nomes de todos os sources embutidos, na ordem usada por get_all_sources
+pub const SOURCE_NAMES: &[&str] = &[
+    "virustotal",
+    "securitytrails",
+    "shodan",
+    "chaos",
+    "github",
+    "netlas",
+    "crtsh",
+    "hackertarget",
+    "rapiddns",
+    "waybackarchive",
+    "fullhunt",
+    "commoncrawl",
+    "fofa",
+    "hunter",
+];
+
 // Função para obter todos os sources disponíveis com configuração
 pub fn get_all_sources(config: &Config) -> Vec<Box<dyn Source>> {
-    vec![
-        "virustotal",
-        "securitytrails", 
-        "shodan",
-        "chaos",
-        "github",
-        "netlas",
-        "crtsh",
-        "hackertarget",
-    ]
-    .into_iter()
-    .filter_map(|name| create_source(name, config))
-    .collect()
+    SOURCE_NAMES
+        .iter()
+        .filter_map(|name| create_source(name, config))
+        .collect()
+}
+
+// Registro de sources externos, para uso da crate como biblioteca
+pub type SourceFactory = Box<dyn Fn(&Config) -> Box<dyn Source> + Send + Sync>;
+
+#[derive(Default)]
+pub struct SourceRegistry {
+    factories: HashMap<String, SourceFactory>,
+}
+
+impl SourceRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register<F>(&mut self, name: &str, factory: F)
+    where
+        F: Fn(&Config) -> Box<dyn Source> + Send + Sync + 'static,
+    {
+        self.factories.insert(name.to_lowercase(), Box::new(factory));
+    }
+
+    pub fn create(&self, name: &str, config: &Config) -> Option<Box<dyn Source>> {
+        self.factories.get(&name.to_lowercase()).map(|factory| factory(config))
+    }
+
+    pub fn create_all(&self, config: &Config) -> Vec<Box<dyn Source>> {
+        let mut names: Vec<&String> = self.factories.keys().collect();
+        names.sort();
+        names.into_iter()
+            .filter_map(|name| self.create(name, config))
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.factories.is_empty()
+    }
+}
+
+// Capacidade do canal usado por enumerate_stream
+pub const STREAM_BUFFER: usize = 256;
+
+// Roda um produtor de enumerate_stream e junta tudo num Vec; permite que uma fonte que
+// sobrescreve enumerate_stream implemente enumerate em cima dele
+pub async fn collect_stream<F, Fut>(produce: F) -> Result<Vec<SubdomainResult>, RustFinderError>
+where
+    F: FnOnce(Sender<SubdomainResult>) -> Fut,
+    Fut: Future<Output = Result<(), RustFinderError>>,
+{
+    let (tx, mut rx) = tokio::sync::mpsc::channel(STREAM_BUFFER);
+    let consumer = async move {
+        let mut results = Vec::new();
+        while let Some(result) = rx.recv().await {
+            results.push(result);
+        }
+        results
+    };
+    let (outcome, results) = tokio::join!(produce(tx), consumer);
+    outcome.map(|()| results)
+}
+
+// Máximo de páginas em voo em fetch_pages
+pub const PAGE_CONCURRENCY: usize = 3;
+
+// Paginação concorrente: quando a API informa de antemão quantas páginas existem
+// (SecurityTrails) ou ao menos se há mais (Shodan), a fonte busca a primeira página
+// e passa as seguintes para cá. O fetch de cada página deve chamar check_rate_limit.
+// Os resultados voltam na ordem das páginas
+pub async fn fetch_pages<T, F, Fut>(pages: impl IntoIterator<Item = u32>, fetch: F) -> Vec<(u32, Result<T, RustFinderError>)>
+where
+    F: Fn(u32) -> Fut,
+    Fut: Future<Output = Result<T, RustFinderError>>,
+{
+    let mut fetched: Vec<(u32, Result<T, RustFinderError>)> = futures::stream::iter(pages)
+        .map(|page| {
+            let request = fetch(page);
+            async move { (page, request.await) }
+        })
+        .buffer_unordered(PAGE_CONCURRENCY)
+        .collect()
+        .await;
+    fetched.sort_by_key(|(page, _)| *page);
+    fetched
+}
+
+// Distribui os resultados de uma consulta em lote entre os domínios consultados; um nome sob
+// domínios aninhados (a.dev.example.com com example.com e dev.example.com) vai para ambos
+pub fn split_by_domain(results: Vec<SubdomainResult>, domains: &[String]) -> HashMap<String, Vec<SubdomainResult>> {
+    let mut by_domain: HashMap<String, Vec<SubdomainResult>> =
+        domains.iter().map(|domain| (domain.clone(), Vec::new())).collect();
+    let mut seen = std::collections::HashSet::new();
+    for result in results {
+        let name = result.subdomain.trim_end_matches('.').to_lowercase();
+        for domain in domains {
+            if name.ends_with(&format!(".{}", domain)) && seen.insert((domain.clone(), name.clone())) {
+                if let Some(entries) = by_domain.get_mut(domain) {
+                    entries.push(SubdomainResult { subdomain: name.clone(), ..result.clone() });
+                }
+            }
+        }
+    }
+    by_domain
+}
+
+// Tenta cada API key configurada (em ordem aleatória) até uma funcionar; falhas de
+// autenticação ou de limite passam para a próxima chave, outros erros encerram na hora
+pub async fn with_key_rotation<'a, T, F, Fut>(
+    source_name: &str,
+    keys: &'a [String],
+    mut attempt: F,
+) -> Result<T, RustFinderError>
+where
+    F: FnMut(&'a str) -> Fut,
+    Fut: Future<Output = Result<T, RustFinderError>>,
+{
+    let mut order: Vec<&'a String> = keys.iter().collect();
+    order.shuffle(&mut rand::thread_rng());
+
+    let mut last_error = None;
+    for (index, key) in order.iter().copied().enumerate() {
+        match attempt(key.as_str()).await {
+            Ok(value) => return Ok(value),
+            Err(e) if is_key_failure(&e) => {
+                if index + 1 < order.len() {
+                    warn!("[{}] API key rejeitada ou sem cota ({}); tentando a próxima", source_name, e);
+                }
+                last_error = Some(e);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| {
+        RustFinderError::ApiKeyError(format!("{}: nenhuma API key configurada", source_name))
+    }))
+}
+
+// Distribui as requisições entre todas as chaves, uma de cada vez (--key-rotation round-robin).
+// Os sources guardam o pool num Arc para que os clones por domínio continuem a mesma sequência
+#[derive(Debug, Default)]
+pub struct KeyPool {
+    keys: Vec<String>,
+    next: AtomicUsize,
+}
+
+impl KeyPool {
+    pub fn new(keys: Vec<String>) -> Self {
+        Self {
+            keys,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn next_key(&self) -> Option<&str> {
+        if self.keys.is_empty() {
+            return None;
+        }
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.keys.len();
+        Some(self.keys[index].as_str())
+    }
+}
+
+pub fn is_key_failure(error: &RustFinderError) -> bool {
+    match error {
+        RustFinderError::ApiKeyError(_)
+        | RustFinderError::RateLimitError(_)
+        | RustFinderError::RateLimitExceeded { .. } => true,
+        RustFinderError::NetworkError(message) => {
+            message.starts_with("HTTP error: 401") || message.starts_with("HTTP error: 403")
+        }
+        _ => false,
+    }
 }
 
 // Função auxiliar para verificar se um source precisa de API key
 pub fn requires_api_key(source_name: &str) -> bool {
     matches!(
         source_name.to_lowercase().as_str(),
-        "virustotal" | "securitytrails" | "shodan" | "chaos" | "github" | "netlas"
+        "virustotal" | "securitytrails" | "shodan" | "chaos" | "github" | "netlas" | "fullhunt" | "fofa" | "hunter"
     )
 }
 
@@ -119,10 +399,86 @@ mod tests {
         assert!(source.is_none());
     }
 
+    #[test]
+    fn test_source_registry() {
+        let config = Config::default();
+        let mut registry = SourceRegistry::new();
+        assert!(registry.is_empty());
+
+        registry.register("CustomFeed", |_config| Box::new(CrtShSource::new()));
+
+        assert!(registry.create("customfeed", &config).is_some());
+        assert!(registry.create("unknown", &config).is_none());
+        assert_eq!(registry.create_all(&config).len(), 1);
+    }
+
+    #[test]
+    fn test_split_by_domain() {
+        let results = ["a.example.com", "A.Example.com", "x.dev.example.com", "b.example.net", "other.org"]
+            .iter()
+            .map(|name| SubdomainResult {
+                subdomain: name.to_string(),
+                ..Default::default()
+            })
+            .collect();
+        let domains = vec!["example.com".to_string(), "dev.example.com".to_string(), "empty.io".to_string()];
+
+        let split = split_by_domain(results, &domains);
+        let names = |domain: &str| split[domain].iter().map(|r| r.subdomain.as_str()).collect::<Vec<_>>();
+        assert_eq!(names("example.com"), vec!["a.example.com", "x.dev.example.com"]);
+        assert_eq!(names("dev.example.com"), vec!["x.dev.example.com"]);
+        assert!(names("empty.io").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_collect_stream() {
+        let results = collect_stream(|tx| async move {
+            for name in ["a.example.com", "b.example.com"] {
+                let result = SubdomainResult { subdomain: name.to_string(), ..Default::default() };
+                tx.send(result).await.unwrap();
+            }
+            Ok(())
+        })
+        .await
+        .unwrap();
+        assert_eq!(results.len(), 2);
+
+        let failed = collect_stream(|_tx| async { Err(RustFinderError::ParseError("boom".to_string())) }).await;
+        assert!(failed.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_with_key_rotation() {
+        let keys = vec!["bad".to_string(), "good".to_string()];
+        let result = with_key_rotation("test", &keys, |key| async move {
+            if key == "good" {
+                Ok(key.to_string())
+            } else {
+                Err(RustFinderError::ApiKeyError("invalid".to_string()))
+            }
+        })
+        .await;
+        assert_eq!(result.unwrap(), "good");
+
+        let result: Result<(), _> = with_key_rotation("test", &keys, |_| async {
+            Err(RustFinderError::ParseError("boom".to_string()))
+        })
+        .await;
+        assert!(matches!(result, Err(RustFinderError::ParseError(_))));
+    }
+
     #[test]
     fn test_requires_api_key() {
         assert!(requires_api_key("virustotal"));
         assert!(requires_api_key("shodan"));
         assert!(!requires_api_key("invalid"));
     }
+
+    #[test]
+    fn test_key_pool_round_robin() {
+        let pool = KeyPool::new(vec!["a".to_string(), "b".to_string(), "c".to_string()]);
+        let used: Vec<&str> = (0..5).filter_map(|_| pool.next_key()).collect();
+        assert_eq!(used, vec!["a", "b", "c", "a", "b"]);
+        assert!(KeyPool::new(Vec::new()).next_key().is_none());
+    }
 }
\ No newline at end of file
@@ -1,32 +1,34 @@
 // src/sources/netlas.rs
-use crate::sources::Source;
-use crate::types::{RustFinderError, SourceInfo, SubdomainResult};
+use crate::sources::{split_by_domain, with_key_rotation, Source};
+use crate::types::{RustFinderError, SourceCategory, SourceInfo, SubdomainResult};
 use crate::session::Session;
 use async_trait::async_trait;
 use log::{info, warn};
 use serde::Deserialize;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
+// Os itens ficam como Value: um registro malformado é ignorado em vez de derrubar a resposta inteira
 #[derive(Debug, Deserialize)]
 struct NetlasResponse {
-    items: Vec<NetlasItem>,
+    #[serde(default)]
+    items: Vec<serde_json::Value>,
     count: Option<u32>,
 }
 
-#[derive(Debug, Deserialize)]
-struct NetlasItem {
-    data: NetlasData,
+fn item_domain(item: &serde_json::Value) -> Option<&str> {
+    item.get("data")?.get("domain")?.as_str()
 }
 
-#[derive(Debug, Deserialize)]
-struct NetlasData {
-    domain: Option<String>,
-}
+const DEFAULT_BASE_URL: &str = "https://app.netlas.io/api";
+const PAGE_SIZE: usize = 100;
+// Domínios por consulta em lote
+const BATCH_SIZE: usize = 10;
 
 #[derive(Debug, Clone)]
 pub struct NetlasSource {
     name: String,
     api_keys: Vec<String>,
+    base_url: String,
 }
 
 impl Default for NetlasSource {
@@ -40,6 +42,7 @@ impl NetlasSource {
         Self {
             name: "netlas".to_string(),
             api_keys: Vec::new(),
+            base_url: DEFAULT_BASE_URL.to_string(),
         }
     }
 
@@ -48,15 +51,150 @@ impl NetlasSource {
         self
     }
 
-    fn get_random_api_key(&self) -> Option<&String> {
-        if self.api_keys.is_empty() {
-            None
-        } else {
-            use rand::seq::SliceRandom;
-            let mut rng = rand::thread_rng();
-            self.api_keys.choose(&mut rng)
+    pub fn with_base_url(mut self, base_url: Option<String>) -> Self {
+        if let Some(url) = base_url {
+            self.base_url = url.trim_end_matches('/').to_string();
+        }
+        self
+    }
+
+    // Uma página da busca; devolve os nomes encontrados e quantos itens vieram na resposta
+    async fn fetch_page(
+        &self,
+        query: &str,
+        start: usize,
+        api_key: &str,
+        session: &Session,
+    ) -> Result<(Vec<String>, usize), RustFinderError> {
+        session.check_rate_limit(&self.name).await?;
+
+        let url = format!("{}/domains/", self.base_url);
+        let start = start.to_string();
+        let page_size = PAGE_SIZE.to_string();
+
+        let request_builder = session.client
+            .get(&url)
+            .query(&[
+                ("q", query),
+                ("fields", "domain"),
+                ("source_type", "include"),
+                ("start", start.as_str()),
+                ("size", page_size.as_str()),
+            ])
+            .header("Accept", "application/json")
+            .header("Authorization", format!("Bearer {}", api_key));
+
+        let response = session.send_request_with_retry(request_builder, &self.name).await?;
+        let status = response.status();
+
+        if !status.is_success() {
+            let text = response.text().await
+                .unwrap_or_else(|_| "Failed to read response body".to_string());
+
+            if status.as_u16() == 429 {
+                return Err(RustFinderError::RateLimitError(self.name.to_string()));
+            }
+
+            return Err(RustFinderError::SourceError {
+                source_name: self.name.to_string(),
+                message: format!("Netlas API returned status: {}. Body: {}", status, text),
+            });
+        }
+
+        let text = session.read_text(response).await?;
+
+        let netlas_response: NetlasResponse = serde_json::from_str(&text)
+            .map_err(|e| RustFinderError::JsonParseError(e.to_string(), text))?;
+
+        let names: Vec<String> = netlas_response.items
+            .iter()
+            .filter_map(item_domain)
+            .map(|name| name.trim_end_matches('.').to_lowercase())
+            .collect();
+
+        let skipped = netlas_response.items.len() - names.len();
+        if skipped > 0 {
+            warn!("[{}] {} registros malformados ignorados", self.name, skipped);
+        }
+
+        Ok((names, netlas_response.items.len()))
+    }
+
+    async fn enumerate_with_key(
+        &self,
+        domain: &str,
+        api_key: &str,
+        session: &Session,
+    ) -> Result<Vec<SubdomainResult>, RustFinderError> {
+        let mut results = Vec::new();
+        let mut found_subdomains = HashSet::new();
+
+        let query = format!("domain:*.{}", domain);
+        let (names, _) = self.fetch_page(&query, 0, api_key, session).await?;
+
+        for subdomain in names {
+            if subdomain.ends_with(domain) &&
+               subdomain != domain &&
+               found_subdomains.insert(subdomain.clone()) {
+                results.push(SubdomainResult {
+                    subdomain,
+                    source: self.name.to_string(),
+                    ..Default::default()
+                });
+            }
         }
+
+        info!("[{}] Encontrados {} subdomínios únicos", self.name, results.len());
+        Ok(results)
     }
+
+    // Uma consulta com OR para o grupo inteiro, paginando até uma página por domínio do grupo
+    async fn enumerate_batch_with_key(
+        &self,
+        domains: &[String],
+        api_key: &str,
+        session: &Session,
+    ) -> Result<HashMap<String, Vec<SubdomainResult>>, RustFinderError> {
+        let query = batch_query(domains);
+        let mut results = Vec::new();
+        let mut exhausted = false;
+
+        for page in 0..domains.len() {
+            let (names, items) = self.fetch_page(&query, page * PAGE_SIZE, api_key, session).await?;
+            results.extend(names.into_iter().map(|subdomain| SubdomainResult {
+                subdomain,
+                source: self.name.to_string(),
+                ..Default::default()
+            }));
+            if items < PAGE_SIZE {
+                exhausted = true;
+                break;
+            }
+        }
+
+        Ok(limit_per_domain(split_by_domain(results, domains), exhausted))
+    }
+}
+
+// Mesma cota da consulta individual (uma página por domínio). Se a paginação parou antes do fim,
+// um domínio com menos que a cota pode ter sido ofuscado pelos outros: fica fora do lote e é
+// consultado sozinho
+fn limit_per_domain(
+    mut by_domain: HashMap<String, Vec<SubdomainResult>>,
+    exhausted: bool,
+) -> HashMap<String, Vec<SubdomainResult>> {
+    for results in by_domain.values_mut() {
+        results.truncate(PAGE_SIZE);
+    }
+    if !exhausted {
+        by_domain.retain(|_, results| results.len() >= PAGE_SIZE);
+    }
+    by_domain
+}
+
+fn batch_query(domains: &[String]) -> String {
+    let patterns: Vec<String> = domains.iter().map(|domain| format!("*.{}", domain)).collect();
+    format!("domain:({})", patterns.join(" OR "))
 }
 
 #[async_trait]
@@ -70,6 +208,7 @@ impl Source for NetlasSource {
             name: self.name().to_string(),
             needs_key: true,
             is_default: true,
+            category: SourceCategory::Search,
         }
     }
 
@@ -77,79 +216,96 @@ impl Source for NetlasSource {
         Box::new(self.clone())
     }
 
+    async fn validate_key(&self, key: &str, session: &Session) -> Result<(), RustFinderError> {
+        let url = format!("{}/users/current/", self.base_url);
+        let request_builder = session.client
+            .get(&url)
+            .header("Accept", "application/json")
+            .header("Authorization", format!("Bearer {}", key));
+        session.send_request_with_retry(request_builder, &self.name).await?;
+        Ok(())
+    }
+
     async fn enumerate(&self, domain: &str, session: &Session) -> Result<Vec<SubdomainResult>, RustFinderError> {
-        let api_key = match self.get_random_api_key() {
-            Some(key) => key,
-            None => {
-                warn!("[{}] Pulando fonte: Nenhuma API key configurada.", self.name);
-                return Ok(Vec::new());
-            }
-        };
+        if self.api_keys.is_empty() {
+            warn!("[{}] Pulando fonte: Nenhuma API key configurada.", self.name);
+            return Ok(Vec::new());
+        }
 
-        session.check_rate_limit(&self.name).await?;
+        with_key_rotation(&self.name, &self.api_keys, |api_key| {
+            self.enumerate_with_key(domain, api_key, session)
+        })
+        .await
+    }
 
-        let mut results = Vec::new();
-        let mut found_subdomains = HashSet::new();
-        
-        let query = format!("domain:*.{}", domain);
-        let url = "https://app.netlas.io/api/domains/";
-        
-        let request_builder = session.client
-            .get(url)
-            .query(&[
-                ("q", query.as_str()),
-                ("fields", "domain"),
-                ("source_type", "include"),
-                ("size", "100")
-            ])
-            .header("Accept", "application/json")
-            .header("Authorization", format!("Bearer {}", api_key));
+    fn supports_batch(&self) -> bool {
+        !self.api_keys.is_empty()
+    }
 
-        match session.send_request_with_retry(request_builder, &self.name).await {
-            Ok(response) => {
-                let status = response.status();
-                
-                if !status.is_success() {
-                    let text = response.text().await
-                        .unwrap_or_else(|_| "Failed to read response body".to_string());
-                    
-                    if status.as_u16() == 429 {
-                        return Err(RustFinderError::RateLimitError(self.name.to_string()));
-                    }
-                    
-                    return Err(RustFinderError::SourceError {
-                        source_name: self.name.to_string(),
-                        message: format!("Netlas API returned status: {}. Body: {}", status, text),
-                    });
-                }
-
-                let text = response.text().await
-                    .map_err(|e| RustFinderError::NetworkError(e.to_string()))?;
-
-                let netlas_response: NetlasResponse = serde_json::from_str(&text)
-                    .map_err(|e| RustFinderError::JsonParseError(e.to_string(), text))?;
-
-                for item in netlas_response.items {
-                    if let Some(subdomain) = item.data.domain {
-                        let subdomain = subdomain.trim_end_matches('.').to_lowercase();
-                        
-                        if subdomain.ends_with(domain) && 
-                           subdomain != domain &&
-                           found_subdomains.insert(subdomain.clone()) {
-                            results.push(SubdomainResult {
-                                subdomain,
-                                source: self.name.to_string(),
-                                resolved: false,
-                                ip_addresses: Vec::new(),
-                            });
-                        }
-                    }
-                }
-
-                info!("[{}] Encontrados {} subdomínios únicos", self.name, results.len());
-                Ok(results)
-            }
-            Err(e) => Err(e),
+    async fn enumerate_batch(
+        &self,
+        domains: &[String],
+        session: &Session,
+    ) -> Result<HashMap<String, Vec<SubdomainResult>>, RustFinderError> {
+        let mut results = HashMap::new();
+        for group in domains.chunks(BATCH_SIZE) {
+            let group_results = with_key_rotation(&self.name, &self.api_keys, |api_key| {
+                self.enumerate_batch_with_key(group, api_key, session)
+            })
+            .await?;
+            results.extend(group_results);
         }
+
+        let total: usize = results.values().map(Vec::len).sum();
+        info!("[{}] Encontrados {} subdomínios em {} domínios (lote)", self.name, total, domains.len());
+        Ok(results)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_malformed_items_are_skipped() {
+        let text = r#"{"items": [
+            {"data": {"domain": "a.example.com"}},
+            {"data": {"domain": 42}},
+            {"data": null},
+            "garbage",
+            {"data": {"domain": "b.example.com"}}
+        ], "count": 5}"#;
+        let response: NetlasResponse = serde_json::from_str(text).unwrap();
+        let domains: Vec<&str> = response.items.iter().filter_map(item_domain).collect();
+        assert_eq!(domains, vec!["a.example.com", "b.example.com"]);
+    }
+
+    #[test]
+    fn test_limit_per_domain() {
+        let results = |domain: &str, count: usize| -> Vec<SubdomainResult> {
+            (0..count).map(|i| SubdomainResult {
+                subdomain: format!("h{}.{}", i, domain),
+                ..Default::default()
+            }).collect()
+        };
+        let by_domain = || HashMap::from([
+            ("example.com".to_string(), results("example.com", PAGE_SIZE + 50)),
+            ("example.net".to_string(), results("example.net", 3)),
+        ]);
+
+        let complete = limit_per_domain(by_domain(), true);
+        assert_eq!(complete["example.com"].len(), PAGE_SIZE);
+        assert_eq!(complete["example.net"].len(), 3);
+
+        // Paginação truncada: example.net volta para a consulta individual
+        let truncated = limit_per_domain(by_domain(), false);
+        assert_eq!(truncated["example.com"].len(), PAGE_SIZE);
+        assert!(!truncated.contains_key("example.net"));
+    }
+
+    #[test]
+    fn test_batch_query() {
+        let domains = vec!["example.com".to_string(), "example.net".to_string()];
+        assert_eq!(batch_query(&domains), "domain:(*.example.com OR *.example.net)");
+    }
+}
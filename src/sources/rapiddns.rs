@@ -0,0 +1,141 @@
+// src/sources/rapiddns.rs
+use crate::session::Session;
+use crate::sources::Source;
+use crate::types::{RustFinderError, SourceCategory, SourceInfo, SubdomainResult};
+use crate::utils;
+use async_trait::async_trait;
+use log::info;
+use std::collections::HashSet;
+
+// Marcadores das páginas de desafio (Cloudflare e captchas embutidos). "captcha" ou "access denied"
+// sozinhos aparecem em resultados legítimos, como captcha.example.com
+const BLOCK_MARKERS: &[&str] = &["cf-chl-", "challenge-platform", "g-recaptcha", "h-captcha", "<title>just a moment"];
+
+#[derive(Debug, Clone)]
+pub struct RapidDnsSource {
+    name: String,
+}
+
+impl Default for RapidDnsSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RapidDnsSource {
+    pub fn new() -> Self {
+        Self { name: "rapiddns".to_string() }
+    }
+
+    // Bloqueio só com status de bloqueio (ou 200, quando o desafio vem no lugar da página) e um marcador
+    fn is_blocked_page(status: u16, text: &str) -> bool {
+        if !matches!(status, 200 | 403 | 429 | 503) {
+            return false;
+        }
+        let lower = text.to_lowercase();
+        BLOCK_MARKERS.iter().any(|marker| lower.contains(marker))
+    }
+
+    fn blocked_error(&self) -> RustFinderError {
+        RustFinderError::SourceError {
+            source_name: self.name.to_string(),
+            message: "Received captcha/block page instead of results".to_string(),
+        }
+    }
+
+    // Um 403 chega como NetworkError("HTTP error: 403 Forbidden - <corpo>")
+    fn map_error(&self, error: RustFinderError) -> RustFinderError {
+        match error {
+            RustFinderError::NetworkError(message) => {
+                let status = message
+                    .strip_prefix("HTTP error: ")
+                    .and_then(|rest| rest.get(..3))
+                    .and_then(|code| code.parse().ok());
+                match status {
+                    Some(status) if Self::is_blocked_page(status, &message) => self.blocked_error(),
+                    _ => RustFinderError::NetworkError(message),
+                }
+            }
+            other => other,
+        }
+    }
+}
+
+#[async_trait]
+impl Source for RapidDnsSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn info(&self) -> SourceInfo {
+        SourceInfo {
+            name: self.name().to_string(),
+            needs_key: false,
+            is_default: false,
+            category: SourceCategory::PassiveDns,
+        }
+    }
+
+    fn clone_source(&self) -> Box<dyn Source> {
+        Box::new(self.clone())
+    }
+
+    async fn enumerate(&self, domain: &str, session: &Session) -> Result<Vec<SubdomainResult>, RustFinderError> {
+        session.check_rate_limit(&self.name).await?;
+
+        let url = format!("https://rapiddns.io/subdomain/{}?full=1", domain);
+
+        let response = session.get(&url, &self.name).await.map_err(|e| self.map_error(e))?;
+        let status = response.status().as_u16();
+        let text = session.read_text(response).await?;
+
+        if Self::is_blocked_page(status, &text) {
+            return Err(self.blocked_error());
+        }
+
+        let mut found_subdomains = HashSet::new();
+        let mut results = Vec::new();
+
+        for subdomain in utils::extract_subdomains_from_text(&text, domain)? {
+            if subdomain.ends_with(&format!(".{}", domain)) && found_subdomains.insert(subdomain.clone()) {
+                results.push(SubdomainResult {
+                    subdomain,
+                    source: self.name.to_string(),
+                    ..Default::default()
+                });
+            }
+        }
+
+        info!("[{}] Encontrados {} subdomínios únicos", self.name, results.len());
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_page_needs_status_and_marker() {
+        let challenge = "<html><head><title>Just a moment...</title></head><script src=\"/cdn-cgi/challenge-platform/x.js\"></script></html>";
+        assert!(RapidDnsSource::is_blocked_page(403, challenge));
+        assert!(RapidDnsSource::is_blocked_page(200, challenge));
+        assert!(!RapidDnsSource::is_blocked_page(404, challenge));
+
+        // Resultados que citam "captcha" ou "access denied" não são bloqueio
+        let results = "<td>captcha.example.com</td><td>access-denied.example.com</td><td>access denied</td>";
+        assert!(!RapidDnsSource::is_blocked_page(200, results));
+    }
+
+    #[test]
+    fn test_forbidden_challenge_becomes_block_error() {
+        let source = RapidDnsSource::new();
+        let blocked = source.map_error(RustFinderError::NetworkError(
+            "HTTP error: 403 Forbidden - <div class=\"cf-chl-widget\"></div>".to_string(),
+        ));
+        assert!(matches!(blocked, RustFinderError::SourceError { message, .. } if message.contains("captcha")));
+
+        let other = source.map_error(RustFinderError::NetworkError("HTTP error: 404 Not Found - ".to_string()));
+        assert!(matches!(other, RustFinderError::NetworkError(_)));
+    }
+}
@@ -1,11 +1,12 @@
 // src/sources/securitytrails.rs
 use crate::session::Session;
-use crate::sources::Source;
-use crate::types::{RustFinderError, SourceInfo, SubdomainResult};
+use crate::sources::{fetch_pages, with_key_rotation, KeyPool, Source};
+use crate::types::{KeyRotation, RustFinderError, SourceCategory, SourceInfo, SubdomainResult, DEFAULT_MAX_PAGES};
 use async_trait::async_trait;
 use log::{info, warn};
 use serde::Deserialize;
 use std::collections::HashSet;
+use std::sync::Arc;
 
 #[derive(Debug, Deserialize)]
 struct SecurityTrailsResponse {
@@ -19,10 +20,34 @@ struct SecurityTrailsMeta {
     limit_reached: Option<bool>,
 }
 
+// Listagem paginada (/domains/list): a primeira página já traz o total de páginas
+#[derive(Debug, Deserialize)]
+struct DomainListResponse {
+    #[serde(default)]
+    records: Vec<DomainListRecord>,
+    meta: Option<DomainListMeta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DomainListRecord {
+    hostname: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DomainListMeta {
+    total_pages: Option<u32>,
+}
+
+const DEFAULT_BASE_URL: &str = "https://api.securitytrails.com/v1";
+
 #[derive(Debug, Clone)]
 pub struct SecurityTrailsSource {
     name: String,
     api_keys: Vec<String>,
+    base_url: String,
+    max_pages: u32,
+    rotation: KeyRotation,
+    key_pool: Arc<KeyPool>,
 }
 
 impl Default for SecurityTrailsSource {
@@ -36,55 +61,45 @@ impl SecurityTrailsSource {
         Self {
             name: "securitytrails".to_string(),
             api_keys: Vec::new(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            max_pages: DEFAULT_MAX_PAGES,
+            rotation: KeyRotation::Failover,
+            key_pool: Arc::new(KeyPool::default()),
         }
     }
 
     pub fn with_api_keys(mut self, keys: Vec<String>) -> Self {
+        self.key_pool = Arc::new(KeyPool::new(keys.clone()));
         self.api_keys = keys;
         self
     }
 
-    fn get_random_api_key(&self) -> Option<&String> {
-        if self.api_keys.is_empty() {
-            None
-        } else {
-            use rand::seq::SliceRandom;
-            let mut rng = rand::thread_rng();
-            self.api_keys.choose(&mut rng)
-        }
+    pub fn with_max_pages(mut self, max_pages: u32) -> Self {
+        self.max_pages = max_pages.max(1);
+        self
     }
-}
 
-#[async_trait]
-impl Source for SecurityTrailsSource {
-    fn name(&self) -> &str {
-        &self.name
+    pub fn with_rotation(mut self, rotation: KeyRotation) -> Self {
+        self.rotation = rotation;
+        self
     }
 
-    fn info(&self) -> SourceInfo {
-        SourceInfo {
-            name: self.name().to_string(),
-            needs_key: true,
-            is_default: true,
+    pub fn with_base_url(mut self, base_url: Option<String>) -> Self {
+        if let Some(url) = base_url {
+            self.base_url = url.trim_end_matches('/').to_string();
         }
+        self
     }
 
-    fn clone_source(&self) -> Box<dyn Source> {
-        Box::new(self.clone())
-    }
-
-    async fn enumerate(&self, domain: &str, session: &Session) -> Result<Vec<SubdomainResult>, RustFinderError> {
-        let api_key = match self.get_random_api_key() {
-            Some(key) => key,
-            None => {
-                warn!("[{}] Pulando fonte: Nenhuma API key configurada.", self.name);
-                return Ok(Vec::new());
-            }
-        };
-
+    async fn enumerate_with_key(
+        &self,
+        domain: &str,
+        api_key: &str,
+        session: &Session,
+    ) -> Result<Vec<SubdomainResult>, RustFinderError> {
         session.check_rate_limit(&self.name).await?;
 
-        let url = format!("https://api.securitytrails.com/v1/domain/{}/subdomains", domain);
+        let url = format!("{}/domain/{}/subdomains", self.base_url, domain);
         
         let request_builder = session.client
             .get(&url)
@@ -104,8 +119,7 @@ impl Source for SecurityTrailsSource {
                     });
                 }
 
-                let text = response.text().await
-                    .map_err(|e| RustFinderError::NetworkError(e.to_string()))?;
+                let text = session.read_text(response).await?;
 
                 let st_response: SecurityTrailsResponse = serde_json::from_str(&text)
                     .map_err(|e| RustFinderError::JsonParseError(e.to_string(), text))?;
@@ -121,8 +135,7 @@ impl Source for SecurityTrailsSource {
                             results.push(SubdomainResult {
                                 subdomain: full_subdomain,
                                 source: self.name.to_string(),
-                                resolved: false,
-                                ip_addresses: Vec::new(),
+                                ..Default::default()
                             });
                         }
                     }
@@ -131,8 +144,9 @@ impl Source for SecurityTrailsSource {
                 if let Some(meta) = st_response.meta {
                     if let Some(limit_reached) = meta.limit_reached {
                         if limit_reached {
-                            warn!("[{}] Limite de resultados atingido. Total de subdomínios: {:?}", 
+                            warn!("[{}] Limite de resultados atingido (total: {:?}); buscando pela listagem paginada",
                                   self.name, st_response.subdomain_count);
+                            self.enumerate_listing(domain, api_key, session, &mut found_subdomains, &mut results).await;
                         }
                     }
                 }
@@ -143,4 +157,167 @@ impl Source for SecurityTrailsSource {
             Err(e) => Err(e),
         }
     }
-}
\ No newline at end of file
+
+    async fn fetch_list_page(
+        &self,
+        domain: &str,
+        api_key: &str,
+        page: u32,
+        session: &Session,
+    ) -> Result<DomainListResponse, RustFinderError> {
+        session.check_rate_limit(&self.name).await?;
+
+        let url = format!("{}/domains/list", self.base_url);
+        let request_builder = session.client
+            .post(&url)
+            .query(&[("page", page.to_string())])
+            .header("APIKEY", api_key)
+            .header("Accept", "application/json")
+            .json(&serde_json::json!({ "filter": { "apex_domain": domain } }));
+
+        let response = session.send_request_with_retry(request_builder, &self.name).await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(RustFinderError::SourceError {
+                source_name: self.name.to_string(),
+                message: format!("SecurityTrails API returned status: {}", status),
+            });
+        }
+
+        let text = session.read_text(response).await?;
+        serde_json::from_str(&text).map_err(|e| RustFinderError::JsonParseError(e.to_string(), text))
+    }
+
+    // Primeira página sequencial (traz total_pages); as demais seguem o padrão de fetch_pages
+    async fn enumerate_listing(
+        &self,
+        domain: &str,
+        api_key: &str,
+        session: &Session,
+        found_subdomains: &mut HashSet<String>,
+        results: &mut Vec<SubdomainResult>,
+    ) {
+        let first_page = match self.fetch_list_page(domain, api_key, 1, session).await {
+            Ok(response) => response,
+            Err(e) => {
+                warn!("[{}] Falha na listagem paginada: {}", self.name, e);
+                return;
+            }
+        };
+        let total_pages = first_page.meta.as_ref()
+            .and_then(|meta| meta.total_pages)
+            .unwrap_or(1)
+            .min(self.max_pages);
+
+        let mut pages = vec![(1, Ok(first_page))];
+        pages.extend(fetch_pages(2..=total_pages, |page| self.fetch_list_page(domain, api_key, page, session)).await);
+
+        let suffix = format!(".{}", domain);
+        for (page, response) in pages {
+            let response = match response {
+                Ok(response) => response,
+                Err(e) => {
+                    warn!("[{}] Falha ao buscar a página {}: {}", self.name, page, e);
+                    continue;
+                }
+            };
+            for hostname in response.records.into_iter().filter_map(|record| record.hostname) {
+                let hostname = hostname.trim_end_matches('.').to_lowercase();
+                if hostname.ends_with(&suffix) && found_subdomains.insert(hostname.clone()) {
+                    results.push(SubdomainResult {
+                        subdomain: hostname,
+                        source: self.name.to_string(),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Source for SecurityTrailsSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn info(&self) -> SourceInfo {
+        SourceInfo {
+            name: self.name().to_string(),
+            needs_key: true,
+            is_default: true,
+            category: SourceCategory::PassiveDns,
+        }
+    }
+
+    fn clone_source(&self) -> Box<dyn Source> {
+        Box::new(self.clone())
+    }
+
+    async fn validate_key(&self, key: &str, session: &Session) -> Result<(), RustFinderError> {
+        let url = format!("{}/ping", self.base_url);
+        let request_builder = session.client
+            .get(&url)
+            .header("APIKEY", key)
+            .header("Accept", "application/json");
+        session.send_request_with_retry(request_builder, &self.name).await?;
+        Ok(())
+    }
+
+    async fn enumerate(&self, domain: &str, session: &Session) -> Result<Vec<SubdomainResult>, RustFinderError> {
+        if self.api_keys.is_empty() {
+            warn!("[{}] Pulando fonte: Nenhuma API key configurada.", self.name);
+            return Ok(Vec::new());
+        }
+
+        // O pool é compartilhado entre os clones, então cada domínio usa a próxima chave
+        if self.rotation == KeyRotation::RoundRobin {
+            if let Some(api_key) = self.key_pool.next_key() {
+                return self.enumerate_with_key(domain, api_key, session).await;
+            }
+        }
+
+        with_key_rotation(&self.name, &self.api_keys, |api_key| {
+            self.enumerate_with_key(domain, api_key, session)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_limit_reached_uses_paginated_listing() {
+        let mut server = mockito::Server::new_async().await;
+        let _subdomains = server
+            .mock("GET", "/domain/example.com/subdomains")
+            .with_body(r#"{"subdomains": ["www"], "subdomain_count": 3, "meta": {"limit_reached": true}}"#)
+            .create_async()
+            .await;
+        let first = server
+            .mock("POST", "/domains/list")
+            .match_query(mockito::Matcher::UrlEncoded("page".into(), "1".into()))
+            .with_body(r#"{"records": [{"hostname": "www.example.com"}, {"hostname": "api.example.com"}], "meta": {"total_pages": 2}}"#)
+            .create_async()
+            .await;
+        let second = server
+            .mock("POST", "/domains/list")
+            .match_query(mockito::Matcher::UrlEncoded("page".into(), "2".into()))
+            .with_body(r#"{"records": [{"hostname": "mail.example.com"}], "meta": {"total_pages": 2}}"#)
+            .create_async()
+            .await;
+
+        let source = SecurityTrailsSource::new()
+            .with_api_keys(vec!["key".to_string()])
+            .with_base_url(Some(server.url()));
+        let session = Session::new(&crate::types::Config::default()).unwrap();
+        let results = source.enumerate("example.com", &session).await.unwrap();
+
+        let names: Vec<&str> = results.iter().map(|r| r.subdomain.as_str()).collect();
+        assert_eq!(names, vec!["www.example.com", "api.example.com", "mail.example.com"]);
+        first.assert_async().await;
+        second.assert_async().await;
+    }
+}
@@ -1,11 +1,13 @@
 // src/sources/shodan.rs
 use crate::session::Session;
-use crate::sources::Source;
-use crate::types::{RustFinderError, SourceInfo, SubdomainResult};
+use crate::sources::{fetch_pages, with_key_rotation, KeyPool, Source, PAGE_CONCURRENCY};
+use crate::types::{KeyRotation, RustFinderError, SourceCategory, SourceInfo, SubdomainResult, DEFAULT_MAX_PAGES};
 use async_trait::async_trait;
 use log::{info, warn};
 use serde::Deserialize;
-use std::collections::HashSet;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Arc;
 
 #[derive(Debug, Deserialize)]
 struct ShodanResponse {
@@ -24,10 +26,24 @@ struct ShodanData {
     value: Option<String>,
 }
 
+fn record_ip(record_type: Option<&str>, value: Option<&str>) -> Option<String> {
+    match record_type {
+        Some("A") | Some("AAAA") => value?.trim().parse::<IpAddr>().ok().map(|ip| ip.to_string()),
+        _ => None,
+    }
+}
+
+const DEFAULT_BASE_URL: &str = "https://api.shodan.io";
+
+
 #[derive(Debug, Clone)]
 pub struct ShodanSource {
     name: String,
     api_keys: Vec<String>,
+    base_url: String,
+    max_pages: u32,
+    rotation: KeyRotation,
+    key_pool: Arc<KeyPool>,
 }
 
 impl Default for ShodanSource {
@@ -41,22 +57,163 @@ impl ShodanSource {
         Self {
             name: "shodan".to_string(),
             api_keys: Vec::new(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            max_pages: DEFAULT_MAX_PAGES,
+            rotation: KeyRotation::Failover,
+            key_pool: Arc::new(KeyPool::default()),
         }
     }
 
+    pub fn with_max_pages(mut self, max_pages: u32) -> Self {
+        self.max_pages = max_pages.max(1);
+        self
+    }
+
     pub fn with_api_keys(mut self, keys: Vec<String>) -> Self {
+        self.key_pool = Arc::new(KeyPool::new(keys.clone()));
         self.api_keys = keys;
         self
     }
 
-    fn get_random_api_key(&self) -> Option<&String> {
-        if self.api_keys.is_empty() {
-            None
-        } else {
-            use rand::seq::SliceRandom;
-            let mut rng = rand::thread_rng();
-            self.api_keys.choose(&mut rng)
+    pub fn with_rotation(mut self, rotation: KeyRotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_base_url(mut self, base_url: Option<String>) -> Self {
+        if let Some(url) = base_url {
+            self.base_url = url.trim_end_matches('/').to_string();
+        }
+        self
+    }
+
+    async fn fetch_page(
+        &self,
+        domain: &str,
+        keys: &KeyPool,
+        page: u32,
+        session: &Session,
+    ) -> Result<ShodanResponse, RustFinderError> {
+        let api_key = keys.next_key()
+            .ok_or_else(|| RustFinderError::ApiKeyError(format!("{}: nenhuma API key configurada", self.name)))?;
+        session.check_rate_limit(&self.name).await?;
+
+        let url = format!("{}/dns/domain/{}", self.base_url, domain);
+        let request_builder = session.client
+            .get(&url)
+            .query(&[
+                ("key", api_key),
+                ("page", &page.to_string())
+            ])
+            .header("Accept", "application/json");
+
+        let response = session.send_request_with_retry(request_builder, &self.name).await?;
+        let status = response.status();
+
+        if !status.is_success() {
+            let text = response.text().await
+                .unwrap_or_else(|_| "Failed to read response body".to_string());
+
+            if status.as_u16() == 429 || text.contains("rate limit") {
+                return Err(RustFinderError::RateLimitError(self.name.to_string()));
+            }
+
+            return Err(RustFinderError::SourceError {
+                source_name: self.name.to_string(),
+                message: format!("Shodan API returned status: {}. Body: {}", status, text),
+            });
         }
+
+        let text = session.read_text(response).await?;
+        let shodan_response: ShodanResponse = serde_json::from_str(&text)
+            .map_err(|e| RustFinderError::JsonParseError(e.to_string(), text))?;
+
+        if let Some(error) = shodan_response.error {
+            return Err(RustFinderError::SourceError {
+                source_name: self.name.to_string(),
+                message: format!("Shodan API error: {}", error),
+            });
+        }
+
+        Ok(shodan_response)
+    }
+
+    fn collect_page(
+        &self,
+        response: ShodanResponse,
+        domain: &str,
+        found_subdomains: &mut HashMap<String, usize>,
+        results: &mut Vec<SubdomainResult>,
+    ) {
+        let mut push = |full_subdomain: String, ip: Option<String>| {
+            let index = *found_subdomains.entry(full_subdomain.clone()).or_insert_with(|| {
+                results.push(SubdomainResult {
+                    subdomain: full_subdomain,
+                    source: self.name.to_string(),
+                    ..Default::default()
+                });
+                results.len() - 1
+            });
+            // O IP de um registro A/AAAA já conta como resolvido, e o resolvedor pode pular o nome
+            if let Some(ip) = ip {
+                let result = &mut results[index];
+                if !result.ip_addresses.contains(&ip) {
+                    result.ip_addresses.push(ip);
+                }
+                result.resolved = true;
+            }
+        };
+
+        for subdomain in response.subdomains {
+            push(format!("{}.{}", subdomain, response.domain), None);
+        }
+
+        for data in response.data.unwrap_or_default() {
+            if let Some(subdomain) = data.subdomain {
+                let full_subdomain = if subdomain.ends_with(&format!(".{}", domain)) {
+                    subdomain
+                } else {
+                    format!("{}.{}", subdomain, domain)
+                };
+                push(full_subdomain, record_ip(data.record_type.as_deref(), data.value.as_deref()));
+            }
+        }
+    }
+
+    // Cada página pede uma chave ao pool: com uma só chave é sempre a mesma,
+    // em round-robin as páginas se espalham entre todas
+    async fn enumerate_pages(
+        &self,
+        domain: &str,
+        keys: &KeyPool,
+        session: &Session,
+    ) -> Result<Vec<SubdomainResult>, RustFinderError> {
+        let mut results = Vec::new();
+        let mut found_subdomains = HashMap::new();
+
+        let first_page = self.fetch_page(domain, keys, 1, session).await?;
+        let mut more = first_page.more.unwrap_or(false);
+        self.collect_page(first_page, domain, &mut found_subdomains, &mut results);
+
+        // A API não informa o total, só "more": as páginas seguintes são pedidas em janelas
+        // de PAGE_CONCURRENCY, e a primeira página sem "more" encerra a busca
+        let mut next_page = 2;
+        while more && next_page <= self.max_pages {
+            let last_page = (next_page + PAGE_CONCURRENCY as u32 - 1).min(self.max_pages);
+            let pages = fetch_pages(next_page..=last_page, |page| self.fetch_page(domain, keys, page, session)).await;
+            for (_, page) in pages {
+                let response = page?;
+                more = response.more.unwrap_or(false);
+                self.collect_page(response, domain, &mut found_subdomains, &mut results);
+                if !more {
+                    break;
+                }
+            }
+            next_page = last_page + 1;
+        }
+
+        info!("[{}] Encontrados {} subdomínios únicos", self.name, results.len());
+        Ok(results)
     }
 }
 
@@ -71,6 +228,7 @@ impl Source for ShodanSource {
             name: self.name().to_string(),
             needs_key: true,
             is_default: true,
+            category: SourceCategory::PassiveDns,
         }
     }
 
@@ -78,110 +236,100 @@ impl Source for ShodanSource {
         Box::new(self.clone())
     }
 
+    async fn validate_key(&self, key: &str, session: &Session) -> Result<(), RustFinderError> {
+        let url = format!("{}/api-info", self.base_url);
+        let request_builder = session.client.get(&url).query(&[("key", key)]);
+        session.send_request_with_retry(request_builder, &self.name).await?;
+        Ok(())
+    }
+
     async fn enumerate(&self, domain: &str, session: &Session) -> Result<Vec<SubdomainResult>, RustFinderError> {
-        let api_key = match self.get_random_api_key() {
-            Some(key) => key,
-            None => {
-                warn!("[{}] Pulando fonte: Nenhuma API key configurada.", self.name);
-                return Ok(Vec::new());
+        if self.api_keys.is_empty() {
+            warn!("[{}] Pulando fonte: Nenhuma API key configurada.", self.name);
+            return Ok(Vec::new());
+        }
+
+        match self.rotation {
+            KeyRotation::RoundRobin => self.enumerate_pages(domain, &self.key_pool, session).await,
+            KeyRotation::Failover => {
+                with_key_rotation(&self.name, &self.api_keys, |api_key| async move {
+                    let keys = KeyPool::new(vec![api_key.to_string()]);
+                    self.enumerate_pages(domain, &keys, session).await
+                })
+                .await
             }
-        };
+        }
+    }
+}
 
-        session.check_rate_limit(&self.name).await?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
+    #[test]
+    fn test_collect_page_keeps_a_record_ips() {
+        let response: ShodanResponse = serde_json::from_str(r#"{
+            "domain": "example.com",
+            "subdomains": ["www", "mail"],
+            "data": [
+                {"subdomain": "www", "type": "A", "value": "93.184.216.34"},
+                {"subdomain": "www", "type": "A", "value": "93.184.216.34"},
+                {"subdomain": "www", "type": "AAAA", "value": "2606:2800:220:1::1"},
+                {"subdomain": "mail", "type": "MX", "value": "mx.example.net"}
+            ]
+        }"#).unwrap();
+
+        let mut found = HashMap::new();
         let mut results = Vec::new();
-        let mut found_subdomains = HashSet::new();
-        let mut page = 1;
-        let max_pages = 5;
-
-        loop {
-            let url = format!("https://api.shodan.io/dns/domain/{}", domain);
-            
-            let request_builder = session.client
-                .get(&url)
-                .query(&[
-                    ("key", api_key),
-                    ("page", &page.to_string())
-                ])
-                .header("Accept", "application/json");
-            
-            match session.send_request_with_retry(request_builder, &self.name).await {
-                Ok(response) => {
-                    let status = response.status();
-                    
-                    if !status.is_success() {
-                        let text = response.text().await
-                            .unwrap_or_else(|_| "Failed to read response body".to_string());
-                        
-                        if status.as_u16() == 429 || text.contains("rate limit") {
-                            return Err(RustFinderError::RateLimitError(self.name.to_string()));
-                        }
-                        
-                        return Err(RustFinderError::SourceError {
-                            source_name: self.name.to_string(),
-                            message: format!("Shodan API returned status: {}. Body: {}", status, text),
-                        });
-                    }
-
-                    let text = response.text().await
-                        .map_err(|e| RustFinderError::NetworkError(e.to_string()))?;
-
-                    let shodan_response: ShodanResponse = serde_json::from_str(&text)
-                        .map_err(|e| RustFinderError::JsonParseError(e.to_string(), text))?;
-
-                    if let Some(error) = shodan_response.error {
-                        return Err(RustFinderError::SourceError {
-                            source_name: self.name.to_string(),
-                            message: format!("Shodan API error: {}", error),
-                        });
-                    }
-
-                    for subdomain in shodan_response.subdomains {
-                        let full_subdomain = format!("{}.{}", subdomain, shodan_response.domain);
-                        if found_subdomains.insert(full_subdomain.clone()) {
-                            results.push(SubdomainResult {
-                                subdomain: full_subdomain,
-                                source: self.name.to_string(),
-                                resolved: false,
-                                ip_addresses: Vec::new(),
-                            });
-                        }
-                    }
-
-                    if let Some(data_array) = shodan_response.data {
-                        for data in data_array {
-                            if let Some(subdomain) = data.subdomain {
-                                let full_subdomain = if subdomain.ends_with(&format!(".{}", domain)) {
-                                    subdomain
-                                } else {
-                                    format!("{}.{}", subdomain, domain)
-                                };
-                                
-                                if found_subdomains.insert(full_subdomain.clone()) {
-                                    results.push(SubdomainResult {
-                                        subdomain: full_subdomain,
-                                        source: self.name.to_string(),
-                                        resolved: false,
-                                        ip_addresses: Vec::new(),
-                                    });
-                                }
-                            }
-                        }
-                    }
-
-                    if let Some(more) = shodan_response.more {
-                        if more && page < max_pages {
-                            page += 1;
-                            continue;
-                        }
-                    }
-                    break;
-                }
-                Err(e) => return Err(e),
-            }
+        ShodanSource::new().collect_page(response, "example.com", &mut found, &mut results);
+
+        assert_eq!(results.len(), 2);
+        let www = results.iter().find(|r| r.subdomain == "www.example.com").unwrap();
+        assert!(www.resolved);
+        assert_eq!(www.ip_addresses, vec!["93.184.216.34", "2606:2800:220:1::1"]);
+        let mail = results.iter().find(|r| r.subdomain == "mail.example.com").unwrap();
+        assert!(!mail.resolved && mail.ip_addresses.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pages_follow_more() {
+        let mut server = mockito::Server::new_async().await;
+        let mut mocks = Vec::new();
+        for page in 1..=4 {
+            let body = format!(
+                r#"{{"domain": "example.com", "subdomains": ["host{}"], "more": {}}}"#,
+                page,
+                page < 4
+            );
+            mocks.push(
+                server
+                    .mock("GET", "/dns/domain/example.com")
+                    .match_query(mockito::Matcher::UrlEncoded("page".into(), page.to_string()))
+                    .with_body(body)
+                    .create_async()
+                    .await,
+            );
         }
+        let beyond = server
+            .mock("GET", "/dns/domain/example.com")
+            .match_query(mockito::Matcher::UrlEncoded("page".into(), "5".into()))
+            .expect(0)
+            .create_async()
+            .await;
 
-        info!("[{}] Encontrados {} subdomínios únicos", self.name, results.len());
-        Ok(results)
+        let source = ShodanSource::new()
+            .with_api_keys(vec!["key".to_string()])
+            .with_base_url(Some(server.url()))
+            .with_max_pages(10);
+        let session = Session::new(&crate::types::Config::default()).unwrap();
+        let results = source.enumerate("example.com", &session).await.unwrap();
+
+        let mut names: Vec<&str> = results.iter().map(|r| r.subdomain.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["host1.example.com", "host2.example.com", "host3.example.com", "host4.example.com"]);
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+        beyond.assert_async().await;
     }
-}
\ No newline at end of file
+}
@@ -1,7 +1,7 @@
 // src/sources/stubs.rs
 
 use crate::sources::Source;
-use crate::types::{RustFinderError, SourceInfo, SubdomainResult};
+use crate::types::{RustFinderError, SourceCategory, SourceInfo, SubdomainResult};
 use crate::session::Session;
 use async_trait::async_trait;
 
@@ -31,6 +31,7 @@ macro_rules! create_stub_source {
                     name: self.name().to_string(),
                     needs_key: false,
                     is_default: false,
+                    category: SourceCategory::Search,
                 }
             }
 
@@ -56,28 +57,22 @@ macro_rules! create_stub_source {
 // create_stub_source!(CensysSource, "censys");
 // create_stub_source!(CertspotterSource, "certspotter");
 // create_stub_source!(ChinazSource, "chinaz");
-// create_stub_source!(CommoncrawlSource, "commoncrawl");
 // create_stub_source!(DigitalyamaSource, "digitalyama");
 // create_stub_source!(DigitorusSource, "digitorus");
 // create_stub_source!(DnsdbSource, "dnsdb");
 // create_stub_source!(DnsdumpsterSource, "dnsdumpster");
 // create_stub_source!(DnsrepoSource, "dnsrepo");
 // create_stub_source!(FacebookSource, "facebook");
-// create_stub_source!(FofaSource, "fofa");
-// create_stub_source!(FullhuntSource, "fullhunt");
 // create_stub_source!(HudsonrockSource, "hudsonrock");
-// create_stub_source!(HunterSource, "hunter");
 // create_stub_source!(IntelxSource, "intelx");
 // create_stub_source!(LeakixSource, "leakix");
 // create_stub_source!(PugreconSource, "pugrecon");
 // create_stub_source!(QuakeSource, "quake");
-// create_stub_source!(RapiddnsSource, "rapiddns");
 // create_stub_source!(RedhuntlabsSource, "redhuntlabs");
 // create_stub_source!(RobtexSource, "robtex");
 // create_stub_source!(RsecloudSource, "rsecloud");
 // create_stub_source!(SitedossierSource, "sitedossier");
 // create_stub_source!(ThreatbookSource, "threatbook");
 // create_stub_source!(ThreatcrowdSource, "threatcrowd");
-// create_stub_source!(WaybackarchiveSource, "waybackarchive");
 // create_stub_source!(WhoisxmlapiSource, "whoisxmlapi");
 // create_stub_source!(ZoomeyeapiSource, "zoomeyeapi");
\ No newline at end of file
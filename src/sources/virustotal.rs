@@ -1,7 +1,8 @@
 // src/sources/virustotal.rs
 use crate::session::Session;
-use crate::sources::Source;
-use crate::types::{RustFinderError, SourceInfo, SubdomainResult};
+use crate::sources::{with_key_rotation, Source};
+use crate::types::{RustFinderError, SourceCategory, SourceInfo, SubdomainResult};
+use crate::utils;
 use async_trait::async_trait;
 use log::warn;
 use serde::Deserialize;
@@ -56,13 +57,45 @@ impl VirusTotalSource {
         self
     }
 
-    fn get_random_api_key(&self) -> Option<&String> {
-        if self.api_keys.is_empty() {
-            None
-        } else {
-            use rand::seq::SliceRandom;
-            self.api_keys.choose(&mut rand::thread_rng())
+    // Mesma normalização do engine: o apex é descartado, www.{domain} é um subdomínio
+    // comum e ids fora do domínio (foo.other.com) são ignorados em vez de reconstruídos
+    fn full_subdomain(id: &str, domain: &str) -> Option<String> {
+        utils::normalize_result(id, domain)
+    }
+
+    async fn enumerate_with_key(
+        &self,
+        domain: &str,
+        api_key: &str,
+        session: &Session,
+    ) -> Result<Vec<SubdomainResult>, RustFinderError> {
+        session.check_rate_limit(&self.name).await?;
+
+        let url = format!(
+            "https://www.virustotal.com/api/v3/domains/{}/subdomains?limit=100",
+            domain
+        );
+
+        let request_builder = session.client.get(&url).header("x-apikey", api_key);
+        let response = session.send_request_with_retry(request_builder, &self.name).await?;
+
+        let text = session.read_text(response).await?;
+        let data: VirusTotalResponse = serde_json::from_str(&text)
+            .map_err(|e| RustFinderError::JsonParseError(e.to_string(), text))?;
+
+        let mut results = Vec::new();
+
+        for item in data.data {
+            if let Some(subdomain) = Self::full_subdomain(&item.id, domain) {
+                results.push(SubdomainResult {
+                    subdomain,
+                    source: self.name.to_string(),
+                    ..Default::default()
+                });
+            }
         }
+
+        Ok(results)
     }
 }
 
@@ -77,6 +110,7 @@ impl Source for VirusTotalSource {
             name: self.name().to_string(),
             needs_key: true,
             is_default: true,
+            category: SourceCategory::PassiveDns,
         }
     }
 
@@ -84,43 +118,43 @@ impl Source for VirusTotalSource {
         Box::new(self.clone())
     }
 
+    async fn validate_key(&self, key: &str, session: &Session) -> Result<(), RustFinderError> {
+        // A chave vai só no cabeçalho, nunca na URL (que acaba em logs e mensagens de erro)
+        let request_builder = session.client
+            .get("https://www.virustotal.com/api/v3/users/me")
+            .header("x-apikey", key);
+        session.send_request_with_retry(request_builder, &self.name).await?;
+        Ok(())
+    }
+
     async fn enumerate(&self, domain: &str, session: &Session) -> Result<Vec<SubdomainResult>, RustFinderError> {
-        let api_key = match self.get_random_api_key() {
-            Some(key) => key,
-            None => {
-                warn!("[{}] Pulando fonte: Nenhuma API key configurada.", self.name);
-                return Ok(Vec::new());
-            }
-        };
+        if self.api_keys.is_empty() {
+            warn!("[{}] Pulando fonte: Nenhuma API key configurada.", self.name);
+            return Ok(Vec::new());
+        }
 
-        session.check_rate_limit(&self.name).await?;
+        with_key_rotation(&self.name, &self.api_keys, |api_key| {
+            self.enumerate_with_key(domain, api_key, session)
+        })
+        .await
+    }
+}
 
-        let url = format!(
-            "https://www.virustotal.com/api/v3/domains/{}/subdomains?limit=100",
-            domain
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_subdomain() {
+        assert_eq!(VirusTotalSource::full_subdomain("WWW.example.com", "example.com"), Some("www.example.com".to_string()));
+        assert_eq!(VirusTotalSource::full_subdomain("www.example.com.", "example.com"), Some("www.example.com".to_string()));
+        assert_eq!(VirusTotalSource::full_subdomain("api", "example.com"), None);
+        assert_eq!(VirusTotalSource::full_subdomain("foo.other.com", "example.com"), None);
+        assert_eq!(VirusTotalSource::full_subdomain("notexample.com", "example.com"), None);
+        assert_eq!(
+            VirusTotalSource::full_subdomain("example.com.cdn.example.com", "example.com"),
+            Some("example.com.cdn.example.com".to_string())
         );
-
-        let request_builder = session.client.get(&url).header("x-apikey", api_key);
-        let response = session.send_request_with_retry(request_builder, &self.name).await?;
-
-        let data: VirusTotalResponse = response.json().await.map_err(|e| {
-            RustFinderError::JsonParseError(e.to_string(), "Response body not available for VirusTotal JSON parsing error".to_string())
-        })?;
-
-        let mut results = Vec::new();
-
-        for item in data.data {
-            let subdomain = item.id.replace(&format!(".{}", domain), "");
-            if !subdomain.is_empty() && subdomain != domain {
-                results.push(SubdomainResult {
-                    subdomain: subdomain.clone(),
-                    source: self.name.to_string(),
-                    resolved: false,
-                    ip_addresses: Vec::new(),
-                });
-            }
-        }
-
-        Ok(results)
+        assert_eq!(VirusTotalSource::full_subdomain("example.com", "example.com"), None);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,95 @@
+// src/sources/waybackarchive.rs
+use crate::session::Session;
+use crate::sources::{collect_stream, Source};
+use crate::types::{RustFinderError, SourceCategory, SourceInfo, SubdomainResult};
+use crate::utils;
+use async_trait::async_trait;
+use log::info;
+use std::collections::HashSet;
+use tokio::sync::mpsc::Sender;
+
+const MAX_RECORDS: u32 = 50000;
+
+#[derive(Debug, Clone)]
+pub struct WaybackArchiveSource {
+    name: String,
+}
+
+impl Default for WaybackArchiveSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WaybackArchiveSource {
+    pub fn new() -> Self {
+        Self { name: "waybackarchive".to_string() }
+    }
+}
+
+#[async_trait]
+impl Source for WaybackArchiveSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn info(&self) -> SourceInfo {
+        SourceInfo {
+            name: self.name().to_string(),
+            needs_key: false,
+            is_default: false,
+            category: SourceCategory::Web,
+        }
+    }
+
+    fn clone_source(&self) -> Box<dyn Source> {
+        Box::new(self.clone())
+    }
+
+    async fn enumerate(&self, domain: &str, session: &Session) -> Result<Vec<SubdomainResult>, RustFinderError> {
+        collect_stream(|tx| self.enumerate_stream(domain, session, tx)).await
+    }
+
+    // Saída em texto (uma URL por linha), processada conforme chega
+    async fn enumerate_stream(
+        &self,
+        domain: &str,
+        session: &Session,
+        tx: Sender<SubdomainResult>,
+    ) -> Result<(), RustFinderError> {
+        session.check_rate_limit(&self.name).await?;
+
+        let url = format!(
+            "http://web.archive.org/cdx/search/cdx?url=*.{}&fl=original&collapse=urlkey&limit={}",
+            domain, MAX_RECORDS
+        );
+
+        let response = session.get(&url, &self.name).await?;
+        let mut lines = session.read_lines(response);
+
+        let suffix = format!(".{}", domain);
+        let mut found_subdomains = HashSet::new();
+        let mut sent = 0;
+
+        while let Some(line) = lines.next_line().await? {
+            let Ok(host) = utils::extract_domain_from_url(line.trim()) else {
+                continue;
+            };
+            let subdomain = host.to_lowercase();
+            if subdomain.ends_with(&suffix) && found_subdomains.insert(subdomain.clone()) {
+                let result = SubdomainResult {
+                    subdomain,
+                    source: self.name.to_string(),
+                    ..Default::default()
+                };
+                if tx.send(result).await.is_err() {
+                    return Ok(());
+                }
+                sent += 1;
+            }
+        }
+
+        info!("[{}] Encontrados {} subdomínios únicos", self.name, sent);
+        Ok(())
+    }
+}
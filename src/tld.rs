@@ -0,0 +1,84 @@
+// src/tld.rs
+use crate::types::RustFinderError;
+use crate::utils;
+use std::collections::HashSet;
+use std::path::Path;
+
+// Lista embutida no binário; --valid-tlds FILE aceita o tlds-alpha-by-domain.txt da IANA ou o mesmo formato
+const BUNDLED_TLDS: &str = include_str!("../assets/tlds.txt");
+
+#[derive(Debug, Clone, Default)]
+pub struct TldList {
+    tlds: HashSet<String>,
+}
+
+impl TldList {
+    pub fn bundled() -> Self {
+        Self::parse(BUNDLED_TLDS)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, RustFinderError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| RustFinderError::ConfigError(format!("Failed to read TLD list {:?}: {}", path, e)))?;
+        let list = Self::parse(&contents);
+        if list.is_empty() {
+            return Err(RustFinderError::ConfigError(format!("TLD list {:?} has no entries", path)));
+        }
+        Ok(list)
+    }
+
+    // Um TLD por linha; linhas vazias e comentários (#) são ignorados
+    pub fn parse(contents: &str) -> Self {
+        let tlds = contents
+            .lines()
+            .map(|line| line.trim().trim_start_matches('.'))
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| utils::to_ascii_domain(line).unwrap_or_else(|_| line.to_lowercase()))
+            .collect();
+        Self { tlds }
+    }
+
+    // Compara o último rótulo do domínio, em ASCII, com a lista
+    pub fn matches(&self, domain: &str) -> bool {
+        let Some(tld) = domain.trim_end_matches('.').rsplit('.').next() else {
+            return false;
+        };
+        match utils::to_ascii_domain(tld) {
+            Ok(tld) => self.tlds.contains(&tld),
+            Err(_) => false,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.tlds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tlds.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_tlds() {
+        let tlds = TldList::bundled();
+        assert!(tlds.len() > 1000);
+        assert!(tlds.matches("example.com"));
+        assert!(tlds.matches("www.example.co.uk"));
+        assert!(tlds.matches("пример.рф"));
+        assert!(tlds.matches("example.xn--p1ai"));
+        assert!(!tlds.matches("example.con"));
+    }
+
+    #[test]
+    fn test_parse_tlds() {
+        let tlds = TldList::parse("# Version 2024010100\nCOM\n\ncorp\n");
+        assert_eq!(tlds.len(), 2);
+        assert!(tlds.matches("intranet.corp"));
+        assert!(!tlds.matches("example.net"));
+    }
+}
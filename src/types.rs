@@ -4,18 +4,46 @@ use std::collections::HashMap;
 use std::time::Duration;
 use thiserror::Error;
 
+// Limite de páginas compartilhado pelas fontes paginadas
+pub const DEFAULT_MAX_PAGES: u32 = 5;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub timeout: Duration,
+    pub source_timeout: Duration,
+    pub connect_timeout: Duration,
+    pub pool_idle_timeout: Duration,
+    pub pool_max_idle_per_host: usize,
+    pub http_version: HttpVersion,
+    // None mantém o mínimo padrão do backend TLS
+    pub min_tls_version: Option<TlsVersion>,
     pub user_agent: String,
     pub proxy: Option<String>,
+    pub proxy_http: Option<String>,
+    pub proxy_https: Option<String>,
     pub rate_limits: HashMap<String, Option<u32>>,
+    pub global_rate_limit: Option<u32>,
     pub api_keys: HashMap<String, Vec<String>>,
+    pub base_urls: HashMap<String, String>,
     pub output: OutputConfig,
     pub resolver: ResolverConfig,
     pub sources: Vec<String>,
     pub retry_attempts: u32,
+    pub timeout_retries: u32,
     pub retry_delay_ms: u64,
+    pub retry_backoff: BackoffStrategy,
+    pub max_retry_delay_ms: u64,
+    pub max_response_bytes: u64,
+    pub crtsh_timeout: Duration,
+    pub crtsh_max_response_bytes: u64,
+    pub cache_dir: Option<String>,
+    pub cache_ttl: Duration,
+    pub stagger_ms: u64,
+    pub source_priority: Vec<String>,
+    pub dedup_key: DedupKey,
+    pub max_pages: u32,
+    pub key_rotation: KeyRotation,
+    pub include_wildcards: bool,
 }
 
 impl Default for Config {
@@ -27,25 +55,228 @@ impl Default for Config {
         rate_limits.insert("chaos".to_string(), Some(60));
         rate_limits.insert("github".to_string(), Some(5));
         rate_limits.insert("netlas".to_string(), Some(1));
+        rate_limits.insert("rapiddns".to_string(), Some(1));
+        rate_limits.insert("fullhunt".to_string(), Some(1));
+        rate_limits.insert("commoncrawl".to_string(), Some(1));
+        rate_limits.insert("fofa".to_string(), Some(1));
+        rate_limits.insert("hunter".to_string(), Some(1));
 
         Self {
             timeout: Duration::from_secs(30),
+            source_timeout: Duration::from_secs(30),
+            connect_timeout: Duration::from_secs(10),
+            pool_idle_timeout: Duration::from_secs(90),
+            pool_max_idle_per_host: 10,
+            http_version: HttpVersion::Auto,
+            min_tls_version: None,
             user_agent: "RustFinder/1.0".to_string(),
             proxy: None,
+            proxy_http: None,
+            proxy_https: None,
             rate_limits,
+            global_rate_limit: None,
             api_keys: HashMap::new(),
+            base_urls: HashMap::new(),
             output: OutputConfig::default(),
             resolver: ResolverConfig::default(),
-            sources: vec![
-                "virustotal".to_string(),
-                "securitytrails".to_string(),
-                "shodan".to_string(),
-                "chaos".to_string(),
-                "github".to_string(),
-                "netlas".to_string(),
-            ],
+            // Vazio significa "todas as fontes disponíveis"
+            sources: Vec::new(),
             retry_attempts: 3,
+            timeout_retries: 1,
             retry_delay_ms: 500,
+            retry_backoff: BackoffStrategy::Exponential,
+            max_retry_delay_ms: 30_000,
+            max_response_bytes: 50 * 1024 * 1024,
+            crtsh_timeout: Duration::from_secs(20),
+            crtsh_max_response_bytes: 10 * 1024 * 1024,
+            cache_dir: None,
+            cache_ttl: Duration::from_secs(24 * 60 * 60),
+            stagger_ms: 0,
+            source_priority: Vec::new(),
+            dedup_key: DedupKey::Name,
+            max_pages: DEFAULT_MAX_PAGES,
+            key_rotation: KeyRotation::Failover,
+            include_wildcards: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum BackoffStrategy {
+    Constant,
+    Linear,
+    Exponential,
+}
+
+impl std::str::FromStr for BackoffStrategy {
+    type Err = RustFinderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "constant" => Ok(BackoffStrategy::Constant),
+            "linear" => Ok(BackoffStrategy::Linear),
+            "exponential" => Ok(BackoffStrategy::Exponential),
+            other => Err(RustFinderError::ConfigError(format!("Unknown retry backoff strategy: {}", other))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum HttpVersion {
+    // Negocia via ALPN, com HTTP/1.1 como fallback
+    Auto,
+    Http1,
+    // HTTP/2 sem negociação (prior knowledge)
+    Http2,
+}
+
+impl std::str::FromStr for HttpVersion {
+    type Err = RustFinderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(HttpVersion::Auto),
+            "1" | "1.1" | "http1" | "http/1.1" => Ok(HttpVersion::Http1),
+            "2" | "http2" | "http/2" | "h2" => Ok(HttpVersion::Http2),
+            other => Err(RustFinderError::ConfigError(format!(
+                "Unknown HTTP version: {} (expected auto, 1.1 or 2)",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum TlsVersion {
+    Tls1_0,
+    Tls1_1,
+    Tls1_2,
+    Tls1_3,
+}
+
+impl std::str::FromStr for TlsVersion {
+    type Err = RustFinderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().trim_start_matches("tls").trim_start_matches(['v', ' ']) {
+            "1.0" | "1" => Ok(TlsVersion::Tls1_0),
+            "1.1" => Ok(TlsVersion::Tls1_1),
+            "1.2" => Ok(TlsVersion::Tls1_2),
+            "1.3" => Ok(TlsVersion::Tls1_3),
+            _ => Err(RustFinderError::ConfigError(format!(
+                "Unknown TLS version: {} (expected 1.0, 1.1, 1.2 or 1.3)",
+                s
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum SortOrder {
+    Name,
+    Length,
+    Source,
+    Ips,
+}
+
+impl std::str::FromStr for SortOrder {
+    type Err = RustFinderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "name" => Ok(SortOrder::Name),
+            "length" => Ok(SortOrder::Length),
+            "source" => Ok(SortOrder::Source),
+            "ips" => Ok(SortOrder::Ips),
+            other => Err(RustFinderError::ConfigError(format!(
+                "Unknown sort order: {} (expected name, length, source or ips)",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum InputFormat {
+    Lines,
+    Csv,
+    Json,
+}
+
+impl std::str::FromStr for InputFormat {
+    type Err = RustFinderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "lines" => Ok(InputFormat::Lines),
+            "csv" => Ok(InputFormat::Csv),
+            "json" => Ok(InputFormat::Json),
+            other => Err(RustFinderError::ConfigError(format!(
+                "Unknown input format: {} (expected lines, csv or json)",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum KeyRotation {
+    // Uma chave por vez, passando para a próxima só quando ela falha
+    Failover,
+    // Requisições distribuídas entre todas as chaves
+    RoundRobin,
+}
+
+impl std::str::FromStr for KeyRotation {
+    type Err = RustFinderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "failover" => Ok(KeyRotation::Failover),
+            "round-robin" => Ok(KeyRotation::RoundRobin),
+            other => Err(RustFinderError::ConfigError(format!(
+                "Unknown key rotation: {} (expected failover or round-robin)",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum DedupKey {
+    Name,
+    NameSource,
+    NameIp,
+}
+
+impl DedupKey {
+    // Chave usada para agrupar resultados repetidos
+    pub fn key_for(&self, result: &SubdomainResult) -> String {
+        let name = result.subdomain.to_lowercase();
+        match self {
+            DedupKey::Name => name,
+            DedupKey::NameSource => format!("{}|{}", name, result.source.to_lowercase()),
+            DedupKey::NameIp => {
+                let mut ips = result.ip_addresses.clone();
+                ips.sort();
+                format!("{}|{}", name, ips.join(","))
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for DedupKey {
+    type Err = RustFinderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "name" => Ok(DedupKey::Name),
+            "name-source" => Ok(DedupKey::NameSource),
+            "name-ip" => Ok(DedupKey::NameIp),
+            other => Err(RustFinderError::ConfigError(format!(
+                "Unknown dedup key: {} (expected name, name-source or name-ip)",
+                other
+            ))),
         }
     }
 }
@@ -54,8 +285,19 @@ impl Default for Config {
 pub struct OutputConfig {
     pub format: OutputFormat,
     pub file: Option<String>,
+    pub dir: Option<String>,
     pub verbose: bool,
     pub include_ips: bool,
+    pub include_ip_version: bool,
+    pub only_new: bool,
+    pub template: Option<String>,
+    pub sort_by: SortOrder,
+    pub color: bool,
+    pub summary: bool,
+    pub append: bool,
+    pub path_template: Option<String>,
+    // Só os nomes, um por linha, sem [fonte] nem cabeçalho (para encadear com outras ferramentas)
+    pub plain: bool,
 }
 
 impl Default for OutputConfig {
@@ -63,8 +305,18 @@ impl Default for OutputConfig {
         Self {
             format: OutputFormat::Text,
             file: None,
+            dir: None,
             verbose: false,
             include_ips: true,
+            include_ip_version: false,
+            only_new: false,
+            template: None,
+            sort_by: SortOrder::Name,
+            color: false,
+            summary: false,
+            append: false,
+            path_template: None,
+            plain: false,
         }
     }
 }
@@ -76,13 +328,44 @@ pub enum OutputFormat {
     Csv,
 }
 
+impl std::str::FromStr for OutputFormat {
+    type Err = RustFinderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" | "txt" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            other => Err(RustFinderError::ConfigError(format!(
+                "Unknown output format: {} (expected text, json or csv)",
+                other
+            ))),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResolverConfig {
     pub enabled: bool,
     pub threads: usize,
     pub timeout: Duration,
+    // Novas tentativas após uma consulta sem resposta
+    pub retries: usize,
     pub nameservers: Vec<String>,
     pub use_system_resolver: bool,
+    // Testa cada servidor na inicialização; com strict, um servidor inacessível é erro
+    pub probe_nameservers: bool,
+    pub strict: bool,
+    // Distribui as consultas em rodízio, um resolvedor por servidor, em vez da seleção do trust-dns
+    pub rotate_nameservers: bool,
+    pub protocol: ResolverProtocol,
+    pub tls_dns_name: Option<String>,
+    pub records: Vec<String>,
+    pub force_resolve: bool,
+    pub cache: bool,
+    pub filter_wildcards: bool,
+    // Vazio: rótulos aleatórios gerados a cada execução
+    pub wildcard_probes: Vec<String>,
 }
 
 impl Default for ResolverConfig {
@@ -91,6 +374,7 @@ impl Default for ResolverConfig {
             enabled: true,
             threads: 50,
             timeout: Duration::from_secs(5),
+            retries: 2,
             nameservers: vec![
                 "8.8.8.8:53".to_string(),
                 "8.8.4.4:53".to_string(),
@@ -98,16 +382,92 @@ impl Default for ResolverConfig {
                 "1.0.0.1:53".to_string(),
             ],
             use_system_resolver: false,
+            probe_nameservers: false,
+            strict: false,
+            rotate_nameservers: false,
+            protocol: ResolverProtocol::Udp,
+            tls_dns_name: None,
+            records: Vec::new(),
+            force_resolve: false,
+            cache: true,
+            filter_wildcards: false,
+            wildcard_probes: Vec::new(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ResolverProtocol {
+    Udp,
+    Tcp,
+    Https,
+}
+
+impl std::str::FromStr for ResolverProtocol {
+    type Err = RustFinderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "udp" => Ok(ResolverProtocol::Udp),
+            "tcp" => Ok(ResolverProtocol::Tcp),
+            "https" | "doh" => Ok(ResolverProtocol::Https),
+            other => Err(RustFinderError::ConfigError(format!("Unknown resolver protocol: {}", other))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SubdomainResult {
     pub subdomain: String,
     pub source: String,
     pub resolved: bool,
     pub ip_addresses: Vec<String>,
+    #[serde(default)]
+    pub first_seen: Option<String>,
+    #[serde(default)]
+    pub is_new: Option<bool>,
+    #[serde(default)]
+    pub records: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub status: Option<ResolutionStatus>,
+    #[serde(default)]
+    pub reference: Option<String>,
+    #[serde(default)]
+    pub http_status: Option<u16>,
+    // Location de uma resposta 3xx do --http-probe
+    #[serde(default)]
+    pub http_redirect: Option<String>,
+    // Obtido a partir de uma entrada curinga (*.x.example.com → x.example.com)
+    #[serde(default)]
+    pub derived: bool,
+    // Menor TTL da resposta DNS e latência da consulta, quando resolvido nesta execução
+    #[serde(default)]
+    pub ttl: Option<u32>,
+    #[serde(default)]
+    pub resolve_ms: Option<u64>,
+    // Provedor de CDN quando algum IP cai numa faixa conhecida
+    #[serde(default)]
+    pub cdn: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum ResolutionStatus {
+    Resolved,
+    NxDomain,
+    NoRecords,
+    Error,
+}
+
+impl std::fmt::Display for ResolutionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = match self {
+            ResolutionStatus::Resolved => "resolved",
+            ResolutionStatus::NxDomain => "nxdomain",
+            ResolutionStatus::NoRecords => "norecords",
+            ResolutionStatus::Error => "error",
+        };
+        write!(f, "{}", status)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -117,20 +477,127 @@ pub struct EnumerationStats {
     pub resolved_count: usize,
     pub sources_used: Vec<String>,
     pub duration: Duration,
+    #[serde(default)]
+    pub source_stats: HashMap<String, SourceStats>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SourceStats {
+    pub found: usize,
+    pub errors: usize,
+    // Tempo de parede da chamada a enumerate (somado entre domínios)
+    #[serde(default)]
+    pub duration_ms: u64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunStats {
+    #[serde(default)]
+    pub run_id: String,
+    pub started_at: String,
+    pub finished_at: String,
+    #[serde(flatten)]
+    pub stats: EnumerationStats,
+}
+
+// Incrementar sempre que campos do relatório mudarem de forma incompatível
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DomainReport {
+    #[serde(default)]
+    pub schema_version: u32,
+    #[serde(default)]
+    pub tool_version: String,
     pub domain: String,
     pub subdomains: Vec<SubdomainResult>,
     pub stats: EnumerationStats,
     pub timestamp: String,
+    // Mesmo valor em todos os relatórios de uma execução, para correlacioná-los
+    #[serde(default)]
+    pub run_id: String,
+    // Fontes que falharam ou estouraram o timeout; distingue "zero resultados" de "erro"
+    #[serde(default)]
+    pub errors: Vec<SourceError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceError {
+    pub source: String,
+    pub message: String,
+}
+
+impl DomainReport {
+    pub fn new(domain: String, subdomains: Vec<SubdomainResult>, stats: EnumerationStats) -> Self {
+        Self {
+            schema_version: REPORT_SCHEMA_VERSION,
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            domain,
+            subdomains,
+            stats,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            run_id: String::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn with_errors(mut self, errors: Vec<SourceError>) -> Self {
+        self.errors = errors;
+        self
+    }
+
+    pub fn with_run_id(mut self, run_id: &str) -> Self {
+        self.run_id = run_id.to_string();
+        self
+    }
 }
 
 pub struct SourceInfo {
     pub name: String,
     pub needs_key: bool,
     pub is_default: bool,
+    pub category: SourceCategory,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum SourceCategory {
+    // Certificate Transparency: inclui muitos nomes nunca publicados
+    Cert,
+    // DNS passivo: nomes observados em tráfego real
+    PassiveDns,
+    // Motores de busca e bases de varredura
+    Search,
+    // Arquivos e crawlers da web
+    Web,
+}
+
+impl std::fmt::Display for SourceCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            SourceCategory::Cert => "cert",
+            SourceCategory::PassiveDns => "passivedns",
+            SourceCategory::Search => "search",
+            SourceCategory::Web => "web",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl std::str::FromStr for SourceCategory {
+    type Err = RustFinderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cert" => Ok(SourceCategory::Cert),
+            "passivedns" | "passive-dns" => Ok(SourceCategory::PassiveDns),
+            "search" => Ok(SourceCategory::Search),
+            "web" => Ok(SourceCategory::Web),
+            other => Err(RustFinderError::ConfigError(format!(
+                "Unknown source category: {} (expected cert, passivedns, search or web)",
+                other
+            ))),
+        }
+    }
 }
 
 #[derive(Debug, Error)]
@@ -174,6 +641,9 @@ pub enum RustFinderError {
     #[error("Timeout error: {0}")]
     TimeoutError(String),
 
+    #[error("Unresolved subdomains: {0}")]
+    UnresolvedSubdomains(String),
+
     #[error("Rate limit exceeded for {source_name}: {message}")]
     RateLimitExceeded {
         source_name: String,
@@ -1,10 +1,12 @@
 // src/utils.rs
-use crate::types::RustFinderError;
+use crate::types::{RustFinderError, SortOrder, SubdomainResult};
+use ipnetwork::IpNetwork;
 use regex::Regex;
 use std::collections::HashSet;
 use url::Url;
 use std::fs::File;
 use std::io::{self, BufReader, BufRead};
+use std::net::IpAddr;
 use std::path::PathBuf;
 
 pub fn read_lines(path: &PathBuf) -> io::Result<Vec<String>> {
@@ -13,6 +15,86 @@ pub fn read_lines(path: &PathBuf) -> io::Result<Vec<String>> {
     reader.lines().collect()
 }
 
+pub fn read_list_file(path: &PathBuf) -> io::Result<Vec<String>> {
+    Ok(read_lines(path)?
+        .iter()
+        .filter_map(|line| list_entry(line))
+        .map(str::to_string)
+        .collect())
+}
+
+// Primeiro token de uma linha de lista: ignora comentários (#), linhas vazias e anotações
+// depois do valor ("example.com # principal" ou "example.com principal" → "example.com")
+pub fn list_entry(line: &str) -> Option<&str> {
+    let line = line.split('#').next().unwrap_or_default();
+    line.split_whitespace().next()
+}
+
+// list_entry para listas de domínios: sem o aviso, "exa mple.com" viraria "exa" e o resto
+// seria descartado como anotação
+pub fn domain_list_entry(line: &str) -> Option<&str> {
+    let entry = list_entry(line)?;
+    if normalize_input_domain(entry).is_none() {
+        log::warn!("Ignoring list line that does not start with a valid domain: {}", line.trim());
+        return None;
+    }
+    Some(entry)
+}
+
+pub fn parse_cidrs(values: &[String]) -> Result<Vec<IpNetwork>, RustFinderError> {
+    values
+        .iter()
+        .map(|value| {
+            value.trim().parse::<IpNetwork>()
+                .map_err(|e| RustFinderError::ConfigError(format!("Invalid CIDR block {}: {}", value, e)))
+        })
+        .collect()
+}
+
+// Exclusão tem precedência; com blocos de inclusão, ao menos um IP precisa estar neles
+pub fn matches_cidr_filter(ip_addresses: &[String], include: &[IpNetwork], exclude: &[IpNetwork]) -> bool {
+    let ips: Vec<IpAddr> = ip_addresses.iter().filter_map(|ip| ip.parse().ok()).collect();
+
+    if ips.iter().any(|ip| exclude.iter().any(|network| network.contains(*ip))) {
+        return false;
+    }
+
+    include.is_empty() || ips.iter().any(|ip| include.iter().any(|network| network.contains(*ip)))
+}
+
+// Compara só o primeiro rótulo, sem diferenciar maiúsculas; --drop-label tem precedência
+pub fn matches_label_filter(subdomain: &str, drop: &[String], keep: &[String]) -> bool {
+    let label = subdomain.split('.').next().unwrap_or("").to_lowercase();
+    let contains = |patterns: &[String]| patterns.iter().any(|p| label.contains(&p.to_lowercase()));
+
+    if contains(drop) {
+        return false;
+    }
+
+    keep.is_empty() || contains(keep)
+}
+
+// Data de um first_seen como gravado pelas fontes ("2023-01-15T00:00:00" ou só "2023-01-15")
+pub fn first_seen_date(value: &str) -> Option<chrono::NaiveDate> {
+    chrono::NaiveDate::parse_from_str(value.trim().get(..10)?, "%Y-%m-%d").ok()
+}
+
+// rand::thread_rng é um CSPRNG, então os rótulos não são previsíveis pelo alvo
+pub fn random_dns_label(len: usize) -> String {
+    use rand::Rng;
+    const CHARSET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+    let mut rng = rand::thread_rng();
+    (0..len).map(|_| CHARSET[rng.gen_range(0..CHARSET.len())] as char).collect()
+}
+
+pub fn is_valid_dns_label(label: &str) -> bool {
+    !label.is_empty()
+        && label.len() <= 63
+        && !label.starts_with('-')
+        && !label.ends_with('-')
+        && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
 pub fn extract_domain_from_url(url_str: &str) -> Result<String, RustFinderError> {
     let url = Url::parse(url_str)
         .map_err(|e| RustFinderError::InvalidDomain(format!("Invalid URL: {}", e)))?;
@@ -22,6 +104,40 @@ pub fn extract_domain_from_url(url_str: &str) -> Result<String, RustFinderError>
         .map(|s| s.to_string())
 }
 
+pub fn to_ascii_domain(input: &str) -> Result<String, RustFinderError> {
+    idna::domain_to_ascii(input.trim())
+        .map_err(|e| RustFinderError::InvalidDomain(format!("{}: {:?}", input, e)))
+}
+
+pub fn to_unicode_domain(input: &str) -> String {
+    let (unicode, result) = idna::domain_to_unicode(input);
+    match result {
+        Ok(()) => unicode,
+        Err(_) => input.to_string(),
+    }
+}
+
+pub fn normalize_input_domain(input: &str) -> Option<String> {
+    let input = input.trim();
+
+    let host = if input.contains("://") {
+        extract_domain_from_url(input).ok()?
+    } else {
+        input.split(['/', '?', '#']).next().unwrap_or_default().to_string()
+    };
+
+    let mut domain = host.to_lowercase();
+    while domain.ends_with('.') {
+        domain.pop();
+    }
+
+    if is_valid_domain(&domain) {
+        Some(domain)
+    } else {
+        None
+    }
+}
+
 pub fn is_valid_domain(domain: &str) -> bool {
     if domain.is_empty() || domain.len() > 253 {
         return false;
@@ -86,6 +202,23 @@ pub fn clean_subdomain(subdomain: &str, domain: &str) -> String {
     cleaned
 }
 
+pub fn normalize_result(subdomain: &str, domain: &str) -> Option<String> {
+    let mut normalized = subdomain.trim().to_lowercase();
+
+    while normalized.ends_with('.') {
+        normalized.pop();
+    }
+
+    let domain = domain.trim_end_matches('.').to_lowercase();
+    // Entradas curinga (--include-wildcards) são validadas sem o "*."
+    let name = normalized.strip_prefix("*.").unwrap_or(&normalized);
+    if !name.ends_with(&format!(".{}", domain)) || !is_valid_domain(name) {
+        return None;
+    }
+
+    Some(normalized)
+}
+
 pub fn parse_wildcard(pattern: &str) -> Result<Regex, RustFinderError> {
     if !pattern.contains('*') {
         return Err(RustFinderError::InvalidDomain(
@@ -126,23 +259,61 @@ pub fn deduplicate_subdomains(subdomains: Vec<String>) -> Vec<String> {
 }
 
 pub fn sort_by_level(mut subdomains: Vec<String>) -> Vec<String> {
-    subdomains.sort_by(|a, b| {
-        let a_parts = a.split('.').count();
-        let b_parts = b.split('.').count();
-        a_parts.cmp(&b_parts).then(a.cmp(b))
-    });
+    subdomains.sort_by(|a, b| compare_by_level(a, b));
     
     subdomains
 }
 
+pub fn compare_by_level(a: &str, b: &str) -> std::cmp::Ordering {
+    let a_parts = a.split('.').count();
+    let b_parts = b.split('.').count();
+    a_parts.cmp(&b_parts).then(a.cmp(b))
+}
+
+pub fn sort_results(results: &mut [SubdomainResult], order: SortOrder) {
+    match order {
+        SortOrder::Name => results.sort_by(|a, b| a.subdomain.cmp(&b.subdomain)),
+        SortOrder::Length => results.sort_by(|a, b| compare_by_level(&a.subdomain, &b.subdomain)),
+        SortOrder::Source => results.sort_by(|a, b| a.source.cmp(&b.source).then(a.subdomain.cmp(&b.subdomain))),
+        SortOrder::Ips => results.sort_by(|a, b| {
+            let first_ip = |r: &SubdomainResult| {
+                r.ip_addresses.first().and_then(|ip| ip.parse::<std::net::IpAddr>().ok())
+            };
+            b.resolved
+                .cmp(&a.resolved)
+                .then_with(|| first_ip(a).cmp(&first_ip(b)))
+                .then(a.subdomain.cmp(&b.subdomain))
+        }),
+    }
+}
+
+// Expande {file} e {count} no comando de --on-complete; os caminhos vão entre aspas para o shell
+pub fn expand_command_template(template: &str, files: &[String], count: usize) -> String {
+    let files = files.iter().map(|file| shell_quote(file)).collect::<Vec<_>>().join(" ");
+    template
+        .replace("{file}", &files)
+        .replace("{count}", &count.to_string())
+}
+
+fn shell_quote(value: &str) -> String {
+    if cfg!(windows) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
 pub fn terminal_width() -> usize {
     term_size::dimensions().map(|(w, _)| w).unwrap_or(80)
 }
 
 pub fn progress_message(current: usize, total: usize, message: &str) -> String {
-    let _width = terminal_width();
     let progress_width = 20;
-    let filled = (current as f32 / total as f32 * progress_width as f32) as usize;
+    let filled = if total == 0 {
+        progress_width
+    } else {
+        (current.min(total) * progress_width) / total
+    };
     let empty = progress_width - filled;
     
     format!(
@@ -159,6 +330,38 @@ pub fn progress_message(current: usize, total: usize, message: &str) -> String {
 mod tests {
     use super::*;
 
+    #[cfg(unix)]
+    #[test]
+    fn test_expand_command_template() {
+        let files = vec!["out/a.txt".to_string(), "it's.txt".to_string()];
+        assert_eq!(
+            expand_command_template("notify.sh {file} {count}", &files, 42),
+            "notify.sh 'out/a.txt' 'it'\\''s.txt' 42"
+        );
+        assert_eq!(expand_command_template("echo {count}", &[], 0), "echo 0");
+    }
+
+    #[test]
+    fn test_first_seen_date() {
+        let date = chrono::NaiveDate::from_ymd_opt(2023, 1, 15).unwrap();
+        assert_eq!(first_seen_date("2023-01-15T00:00:00"), Some(date));
+        assert_eq!(first_seen_date("2023-01-15"), Some(date));
+        assert_eq!(first_seen_date("15/01/2023"), None);
+        assert_eq!(first_seen_date(""), None);
+    }
+
+    #[test]
+    fn test_list_entry() {
+        assert_eq!(list_entry("example.com # principal"), Some("example.com"));
+        assert_eq!(list_entry("  example.com\tstaging  "), Some("example.com"));
+        assert_eq!(list_entry("# só comentário"), None);
+        assert_eq!(list_entry("   "), None);
+
+        assert_eq!(domain_list_entry("example.com principal"), Some("example.com"));
+        assert_eq!(domain_list_entry("exa mple.com"), None);
+        assert_eq!(domain_list_entry("# só comentário"), None);
+    }
+
     #[test]
     fn test_is_valid_domain() {
         assert!(is_valid_domain("example.com"));
@@ -169,6 +372,97 @@ mod tests {
         assert!(!is_valid_domain("example-.com"));
     }
 
+    #[test]
+    fn test_sort_results() {
+        let result = |subdomain: &str, source: &str, ips: &[&str]| SubdomainResult {
+            subdomain: subdomain.to_string(),
+            source: source.to_string(),
+            resolved: !ips.is_empty(),
+            ip_addresses: ips.iter().map(|ip| ip.to_string()).collect(),
+            ..Default::default()
+        };
+        let mut results = vec![
+            result("a.b.example.com", "crtsh", &[]),
+            result("z.example.com", "virustotal", &["10.0.0.2"]),
+            result("m.example.com", "crtsh", &["10.0.0.1"]),
+        ];
+
+        let names = |results: &[SubdomainResult]| -> Vec<String> {
+            results.iter().map(|r| r.subdomain.clone()).collect()
+        };
+
+        sort_results(&mut results, SortOrder::Length);
+        assert_eq!(names(&results), vec!["m.example.com", "z.example.com", "a.b.example.com"]);
+
+        sort_results(&mut results, SortOrder::Source);
+        assert_eq!(names(&results), vec!["a.b.example.com", "m.example.com", "z.example.com"]);
+
+        sort_results(&mut results, SortOrder::Ips);
+        assert_eq!(names(&results), vec!["m.example.com", "z.example.com", "a.b.example.com"]);
+    }
+
+    #[test]
+    fn test_cidr_filter() {
+        let include = parse_cidrs(&["10.0.0.0/8".to_string()]).unwrap();
+        let exclude = parse_cidrs(&["10.1.0.0/16".to_string()]).unwrap();
+        let ips = |list: &[&str]| -> Vec<String> { list.iter().map(|ip| ip.to_string()).collect() };
+
+        assert!(matches_cidr_filter(&ips(&["10.0.0.1"]), &include, &exclude));
+        assert!(!matches_cidr_filter(&ips(&["10.1.2.3", "10.0.0.1"]), &include, &exclude));
+        assert!(!matches_cidr_filter(&ips(&["192.168.0.1"]), &include, &exclude));
+        assert!(matches_cidr_filter(&ips(&[]), &[], &exclude));
+        assert!(parse_cidrs(&["not-a-cidr".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_label_filter() {
+        let drop = vec!["email".to_string()];
+        let keep = vec!["API".to_string(), "dev".to_string()];
+
+        assert!(!matches_label_filter("email.example.com", &drop, &[]));
+        assert!(matches_label_filter("mail.email.example.com", &drop, &[]));
+        assert!(matches_label_filter("api-v2.example.com", &[], &keep));
+        assert!(!matches_label_filter("www.dev.example.com", &[], &keep));
+        assert!(!matches_label_filter("dev-email.example.com", &drop, &keep));
+    }
+
+    #[test]
+    fn test_dns_labels() {
+        let label = random_dns_label(12);
+        assert_eq!(label.len(), 12);
+        assert!(is_valid_dns_label(&label));
+        assert!(is_valid_dns_label("probe-1"));
+        assert!(!is_valid_dns_label("-probe"));
+        assert!(!is_valid_dns_label("a.b"));
+        assert!(!is_valid_dns_label(&"a".repeat(64)));
+    }
+
+    #[test]
+    fn test_read_list_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sources.txt");
+        std::fs::write(&path, "# curated\ncrtsh\n\n  virustotal  \n#shodan\n").unwrap();
+
+        assert_eq!(read_list_file(&path).unwrap(), vec!["crtsh", "virustotal"]);
+    }
+
+    #[test]
+    fn test_idn_conversion() {
+        assert_eq!(to_ascii_domain("müller.de").unwrap(), "xn--mller-kva.de");
+        assert_eq!(to_ascii_domain("Example.COM").unwrap(), "example.com");
+        assert_eq!(to_unicode_domain("xn--mller-kva.de"), "müller.de");
+        assert_eq!(to_unicode_domain("www.example.com"), "www.example.com");
+    }
+
+    #[test]
+    fn test_normalize_input_domain() {
+        assert_eq!(normalize_input_domain("Example.com"), Some("example.com".to_string()));
+        assert_eq!(normalize_input_domain("https://example.com/path?q=1"), Some("example.com".to_string()));
+        assert_eq!(normalize_input_domain(" example.com. "), Some("example.com".to_string()));
+        assert_eq!(normalize_input_domain("example.com/login"), Some("example.com".to_string()));
+        assert_eq!(normalize_input_domain("not a domain"), None);
+    }
+
     #[test]
     fn test_clean_subdomain() {
         assert_eq!(clean_subdomain("sub.", "example.com"), "sub.example.com");
@@ -176,6 +470,17 @@ mod tests {
         assert_eq!(clean_subdomain("sub.example.com", "example.com"), "sub.example.com");
     }
 
+    #[test]
+    fn test_normalize_result() {
+        assert_eq!(normalize_result("WWW.Example.com.", "example.com"), Some("www.example.com".to_string()));
+        assert_eq!(normalize_result(".www.example.com", "example.com"), None);
+        assert_eq!(normalize_result("example.com", "example.com"), None);
+        assert_eq!(normalize_result("www.notexample.com", "example.com"), None);
+        assert_eq!(normalize_result(&format!("{}.example.com", "a".repeat(64)), "example.com"), None);
+        assert_eq!(normalize_result("*.Dev.example.com", "example.com"), Some("*.dev.example.com".to_string()));
+        assert_eq!(normalize_result("*.example.com", "example.com"), None);
+    }
+
     #[test]
     fn test_deduplicate_subdomains() {
         let subdomains = vec![